@@ -1,13 +1,24 @@
-use crate::{database::model::Project, ui::yn};
+use crate::{
+    database::model::{InstallRecord, Project},
+    ui::prompt::Prompter,
+};
 use directories::ProjectDirs;
 use rusqlite::{Connection, Result};
+use std::path::PathBuf;
 
-pub fn setup_database() -> Result<Connection> {
+/// The version of the tables below, kept in SQLite's `user_version`. Bump it when they change.
+pub const SCHEMA_VERSION: i32 = 1;
+
+pub fn database_path() -> PathBuf {
     let project = ProjectDirs::from("rs", "", "projector").expect("Failed to get project dirs");
-    let data_dir = project.data_dir();
+    project.data_dir().join("projects.db")
+}
 
-    let db_path = data_dir.join("projects.db");
-    std::fs::create_dir_all(data_dir).expect("Failed to create project directory");
+pub fn setup_database() -> Result<Connection> {
+    let db_path = database_path();
+    if let Some(data_dir) = db_path.parent() {
+        std::fs::create_dir_all(data_dir).expect("Failed to create project directory");
+    }
 
     let conn = Connection::open(db_path)?;
     conn.execute(
@@ -21,6 +32,22 @@ pub fn setup_database() -> Result<Connection> {
         )",
         [],
     )?;
+    // Every install or removal projector runs, kept for auditing what it changed.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS installs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            tool TEXT NOT NULL,
+            version TEXT,
+            method TEXT NOT NULL,
+            packages TEXT NOT NULL,
+            command TEXT NOT NULL,
+            success INTEGER NOT NULL,
+            performed_on TIMESTAMP NOT NULL
+        )",
+        [],
+    )?;
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
     Ok(conn)
 }
 
@@ -45,22 +72,57 @@ pub fn get_all_projects(conn: &Connection) -> Result<Vec<Project>> {
     Ok(projects)
 }
 
+/// All recorded installs and removals, oldest first.
+pub fn get_installs(conn: &Connection) -> Result<Vec<InstallRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, action, tool, version, method, packages, command, success, performed_on
+         FROM installs ORDER BY performed_on, id",
+    )?;
+    let record_iter = stmt.query_map([], |row| {
+        Ok(InstallRecord {
+            id: row.get(0)?,
+            action: row.get(1)?,
+            tool: row.get(2)?,
+            version: row.get(3)?,
+            method: row.get(4)?,
+            packages: row.get(5)?,
+            command: row.get(6)?,
+            success: row.get(7)?,
+            performed_on: row.get(8)?,
+        })
+    })?;
+
+    let mut records = Vec::new();
+    for record in record_iter {
+        records.push(record?);
+    }
+    Ok(records)
+}
+
 pub fn delete_project(conn: &Connection, id: i64) -> Result<usize> {
     conn.execute("DELETE FROM projects WHERE id = ?", [id])
 }
 
-pub fn clear() -> Result<usize> {
-    // Although annoying, ask one final time if they really want to clear the database. Note this is irreversible.
-    if yn::ask(
-        "Are you reaalllyyy sure you want to clear the database? This is irreversible. (y/n)",
+pub fn rename_project(conn: &Connection, id: i64, name: &str) -> Result<usize> {
+    conn.execute(
+        "UPDATE projects SET name = ?1 WHERE id = ?2",
+        rusqlite::params![name, id],
     )
-    .expect("Failed to read user input")
+}
+
+pub fn clear(prompter: &mut dyn Prompter) -> Result<usize> {
+    // Although annoying, ask one final time if they really want to clear the database. Note this is irreversible.
+    if prompter
+        .confirm(
+            "Are you reaalllyyy sure you want to clear the database? This is irreversible. (y/n)",
+        )
+        .expect("Failed to read user input")
     {
         println!("Clearing the database... :(");
         let conn = setup_database()?;
         conn.execute("DELETE FROM projects", [])
     } else {
         println!("Exiting as user chose not to clear the database.");
-        return Ok(0);
+        Ok(0)
     }
 }
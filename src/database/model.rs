@@ -5,6 +5,7 @@ use rusqlite::{params, Connection, Result};
 
 use crate::state::languages::ProgrammingLanguage;
 
+#[derive(Debug, Clone)]
 pub struct Project {
     pub id: i64,
     pub type_lang: ProgrammingLanguage,
@@ -35,3 +36,44 @@ impl Project {
         Ok(())
     }
 }
+
+/// One install or removal of a tool that projector ran.
+#[derive(Debug, Clone)]
+pub struct InstallRecord {
+    pub id: i64,
+    /// `install` or `remove`.
+    pub action: String,
+    /// Tool id, e.g. `gcc` or `rust`.
+    pub tool: String,
+    /// The version reported by the tool afterwards, if it could be found.
+    pub version: Option<String>,
+    /// The package manager id, e.g. `apt`, or `installer` for downloaded installers.
+    pub method: String,
+    /// Packages installed or removed, separated by spaces. Packages that were already on the
+    /// machine before an install aren't included, so removing never takes them away.
+    pub packages: String,
+    /// Every command that ran, separated by ` && `.
+    pub command: String,
+    pub success: bool,
+    pub performed_on: NaiveDateTime,
+}
+
+impl InstallRecord {
+    pub fn insert(&mut self, conn: &Connection) -> Result<()> {
+        conn.execute(
+            "INSERT INTO installs (action, tool, version, method, packages, command, success, performed_on) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                self.action,
+                self.tool,
+                self.version,
+                self.method,
+                self.packages,
+                self.command,
+                self.success,
+                self.performed_on
+            ],
+        )?;
+        self.id = conn.last_insert_rowid();
+        Ok(())
+    }
+}
@@ -0,0 +1,874 @@
+use std::collections::HashSet;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+
+use crate::database::manage::{database_path, get_all_projects, SCHEMA_VERSION};
+use crate::install::toolchain;
+use crate::pins;
+use crate::post_setup::editor::ValidEditors;
+use crate::probe;
+use crate::state::app::Config;
+use crate::state::languages::ProgrammingLanguage;
+use crate::util::which;
+
+/// Everything `projector doctor` checks, in the shape `--json` prints.
+#[derive(Serialize)]
+pub struct Report {
+    pub languages: Vec<LanguageReport>,
+    /// Toolchain versions pinned for the current directory.
+    pub pins: Vec<PinReport>,
+    pub config: ConfigReport,
+    pub database: DatabaseReport,
+    pub editor: EditorReport,
+    pub container_runtimes: Vec<ToolReport>,
+    pub path: Vec<PathProblem>,
+    /// How many of the checks above need fixing.
+    pub problems: usize,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Missing,
+    Outdated,
+    Warning,
+    Error,
+}
+
+#[derive(Serialize)]
+pub struct LanguageReport {
+    pub language: String,
+    /// Whether there are projects in the language or config for it. Missing tools only count
+    /// as problems for these.
+    pub used: bool,
+    pub tools: Vec<ToolReport>,
+}
+
+#[derive(Serialize)]
+pub struct ToolReport {
+    pub name: String,
+    pub status: Status,
+    /// Whether the language needs the tool, rather than it being one of several options.
+    pub required: bool,
+    pub path: Option<PathBuf>,
+    pub version: Option<String>,
+    /// The minimum version from the config, if there is one.
+    pub minimum: Option<String>,
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PinReport {
+    pub tool: String,
+    pub pinned: String,
+    pub file: PathBuf,
+    pub status: Status,
+    pub installed: Option<String>,
+    pub path: Option<PathBuf>,
+    /// How projector would install the pinned version, if a version manager for it is around.
+    pub install_command: Option<String>,
+    pub detail: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ConfigReport {
+    pub status: Status,
+    pub path: Option<PathBuf>,
+    pub exists: bool,
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct DatabaseReport {
+    pub status: Status,
+    pub path: PathBuf,
+    pub exists: bool,
+    pub schema_version: Option<i32>,
+    /// What `PRAGMA integrity_check` said: `ok`, or the problems it found.
+    pub integrity: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct EditorReport {
+    pub status: Status,
+    pub editor: String,
+    pub command: String,
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+pub struct PathProblem {
+    pub entry: String,
+    pub problem: String,
+}
+
+/// Tools each language's scaffolder needs, then the ones it can use if they're there.
+fn language_tools(lang: ProgrammingLanguage) -> (&'static [&'static str], &'static [&'static str]) {
+    match lang {
+        ProgrammingLanguage::Rust => (&["rustc", "cargo"], &[]),
+        ProgrammingLanguage::Python => (&["python3"], &["uv", "poetry", "hatch", "pdm"]),
+        ProgrammingLanguage::JavaScript => (&["node", "npm"], &["pnpm", "yarn", "bun"]),
+        ProgrammingLanguage::Java => (&["java"], &["mvn", "gradle"]),
+        ProgrammingLanguage::Kotlin => (&["java"], &["gradle"]),
+        ProgrammingLanguage::C => (&["gcc"], &["cmake", "meson", "ninja", "make"]),
+        ProgrammingLanguage::CPlusPlus => (&["g++"], &["cmake", "meson", "ninja", "make"]),
+        ProgrammingLanguage::CSharp => (&["dotnet"], &[]),
+        ProgrammingLanguage::Go => (&["go"], &[]),
+        ProgrammingLanguage::Swift => (&["swift"], &[]),
+    }
+}
+
+/// Checks the tools for every language, the toolchains pinned for the current directory, the
+/// config, the database, the editor, container runtimes and the PATH, and prints the findings
+/// as text or JSON. Returns how many problems it found.
+pub fn run(json: bool) -> color_eyre::Result<usize> {
+    let report = check();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print(&report);
+    }
+    Ok(report.problems)
+}
+
+/// Checks everything without creating the config or the database if they aren't there yet.
+pub fn check() -> Report {
+    let config_path = confy::get_configuration_file_path("projector", None).ok();
+    let (config, cfg) = check_config(config_path);
+    let used = used_languages(&cfg, &project_languages());
+    let mut report = Report {
+        languages: ProgrammingLanguage::all_langs()
+            .into_iter()
+            .map(|lang| check_language(&cfg, lang, used.contains(&lang)))
+            .collect(),
+        pins: check_pins(),
+        config,
+        database: check_database(),
+        editor: check_editor(&cfg),
+        container_runtimes: ["docker", "podman"]
+            .iter()
+            .map(|runtime| check_container_runtime(runtime))
+            .collect(),
+        path: check_path(
+            &env::var_os("PATH").unwrap_or_default(),
+            toolchain::bin_dir().ok().as_deref(),
+        ),
+        problems: 0,
+    };
+    report.problems = count_problems(&report);
+    report
+}
+
+fn count_problems(report: &Report) -> usize {
+    let tool_problems = report
+        .languages
+        .iter()
+        .filter(|language| language.used)
+        .flat_map(|language| &language.tools)
+        .filter(|tool| is_problem(tool.status, tool.required))
+        .count();
+    let pin_problems = report
+        .pins
+        .iter()
+        .filter(|pin| is_problem(pin.status, true))
+        .count();
+    tool_problems
+        + pin_problems
+        + [
+            report.config.status,
+            report.database.status,
+            report.editor.status,
+        ]
+        .iter()
+        .filter(|status| is_problem(**status, true))
+        .count()
+        + report.path.len()
+}
+
+/// The languages of the projects in the database, read without creating or migrating it.
+fn project_languages() -> Vec<ProgrammingLanguage> {
+    let path = database_path();
+    if !path.exists() {
+        return Vec::new();
+    }
+    Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| get_all_projects(&conn))
+        .map(|projects| projects.into_iter().map(|p| p.type_lang).collect())
+        .unwrap_or_default()
+}
+
+/// Languages with projects or with minimum versions or hooks in the config.
+fn used_languages(cfg: &Config, projects: &[ProgrammingLanguage]) -> HashSet<ProgrammingLanguage> {
+    cfg.min_versions
+        .keys()
+        .chain(cfg.language_hooks.keys())
+        .filter_map(|name| ProgrammingLanguage::from_name(name))
+        .chain(projects.iter().copied())
+        .collect()
+}
+
+fn is_problem(status: Status, required: bool) -> bool {
+    match status {
+        Status::Ok => false,
+        Status::Missing => required,
+        _ => true,
+    }
+}
+
+fn check_language(cfg: &Config, lang: ProgrammingLanguage, used: bool) -> LanguageReport {
+    let minimums = probe::minimums_in(cfg, lang).unwrap_or_default();
+    let (required, optional) = language_tools(lang);
+    let mut names: Vec<(&str, bool)> = required.iter().map(|tool| (*tool, true)).collect();
+    names.extend(optional.iter().map(|tool| (*tool, false)));
+    // Tools with a minimum version are checked even if they aren't in the list above.
+    for (tool, _) in &minimums {
+        if !names.iter().any(|(name, _)| name == tool) {
+            names.push((tool.as_str(), true));
+        }
+    }
+    let tools = names
+        .into_iter()
+        .map(|(name, required)| {
+            let minimum = minimums
+                .iter()
+                .find(|(tool, _)| tool == name)
+                .map(|(_, minimum)| minimum.clone());
+            let mut report = ToolReport {
+                name: name.to_string(),
+                status: Status::Missing,
+                required,
+                path: None,
+                version: None,
+                minimum: minimum.as_ref().map(ToString::to_string),
+                detail: None,
+            };
+            if let Some(info) = probe::probe(name) {
+                report.status = match (&info.version, &minimum) {
+                    (Some(version), Some(minimum)) if version < minimum => Status::Outdated,
+                    (None, Some(_)) => {
+                        report.detail = Some("couldn't tell its version".to_string());
+                        Status::Warning
+                    }
+                    _ => Status::Ok,
+                };
+                report.path = Some(info.path);
+                report.version = info.version.map(|version| version.to_string());
+            }
+            report
+        })
+        .collect();
+    LanguageReport {
+        language: format!("{:?}", lang),
+        used,
+        tools,
+    }
+}
+
+fn check_pins() -> Vec<PinReport> {
+    let Ok(dir) = env::current_dir() else {
+        return Vec::new();
+    };
+    pins::find(&dir)
+        .into_iter()
+        .map(|pin| {
+            let mut report = PinReport {
+                tool: pin.tool.clone(),
+                pinned: pin.version.clone(),
+                file: pin.file.clone(),
+                status: Status::Ok,
+                installed: None,
+                path: None,
+                install_command: None,
+                detail: None,
+            };
+            let Some(binary) = pin.binary() else {
+                report.detail = Some("projector doesn't check this tool".to_string());
+                return report;
+            };
+            let Some(info) = probe::probe_in(binary, &dir) else {
+                report.status = Status::Missing;
+                report.install_command =
+                    pins::install_command(&pin).map(|command| command.join(" "));
+                return report;
+            };
+            match info.version.as_ref().map(|version| pin.matches(version)) {
+                Some(Some(false)) => {
+                    report.status = Status::Warning;
+                    report.detail = Some("doesn't match the pinned version".to_string());
+                    report.install_command =
+                        pins::install_command(&pin).map(|command| command.join(" "));
+                }
+                Some(Some(true)) => {}
+                _ => report.detail = Some("not compared".to_string()),
+            }
+            report.installed = info.version.map(|version| version.to_string());
+            report.path = Some(info.path);
+            report
+        })
+        .collect()
+}
+
+/// Reads and checks the config at `path`, without creating it if it's missing. Returns the
+/// defaults along with the report if it can't be read.
+fn check_config(path: Option<PathBuf>) -> (ConfigReport, Config) {
+    let exists = path.as_ref().is_some_and(|path| path.exists());
+    let mut errors = Vec::new();
+    let loaded = match path.as_ref().filter(|_| exists) {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| toml::from_str::<Config>(&text).map_err(|e| e.to_string())),
+        None => Ok(Config::default()),
+    };
+    let cfg = match loaded {
+        Ok(cfg) => {
+            for lang in ProgrammingLanguage::all_langs() {
+                if let Err(e) = probe::minimums_in(&cfg, lang) {
+                    errors.push(e.to_string());
+                }
+            }
+            for name in cfg.min_versions.keys() {
+                if ProgrammingLanguage::from_name(name).is_none() {
+                    errors.push(format!("Unknown language '{}' in min_versions", name));
+                }
+            }
+            for name in cfg.language_hooks.keys() {
+                if ProgrammingLanguage::from_name(name).is_none() {
+                    errors.push(format!("Unknown language '{}' in language_hooks", name));
+                }
+            }
+            if let Some(proxy) = &cfg.download_proxy {
+                if let Err(e) = reqwest::Proxy::all(proxy) {
+                    errors.push(format!("Invalid download_proxy '{}': {}", proxy, e));
+                }
+            }
+            cfg
+        }
+        Err(e) => {
+            errors.push(e);
+            Config::default()
+        }
+    };
+    errors.dedup();
+    let report = ConfigReport {
+        status: if errors.is_empty() {
+            Status::Ok
+        } else {
+            Status::Error
+        },
+        path,
+        exists,
+        errors,
+    };
+    (report, cfg)
+}
+
+fn check_database() -> DatabaseReport {
+    let path = database_path();
+    let mut report = DatabaseReport {
+        status: Status::Ok,
+        exists: path.exists(),
+        path,
+        schema_version: None,
+        integrity: None,
+        error: None,
+    };
+    // Projector creates it on first use.
+    if !report.exists {
+        return report;
+    }
+    let result = Connection::open_with_flags(&report.path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| {
+            let version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+            let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+            let integrity = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?
+                .join("; ");
+            Ok((version, integrity))
+        });
+    match result {
+        Ok((version, integrity)) => {
+            if integrity != "ok" {
+                report.status = Status::Error;
+            } else if version != SCHEMA_VERSION {
+                report.status = Status::Warning;
+                report.error = Some(format!(
+                    "expected schema version {}; running any projector command will update it",
+                    SCHEMA_VERSION
+                ));
+            }
+            report.schema_version = Some(version);
+            report.integrity = Some(integrity);
+        }
+        Err(e) => {
+            report.status = Status::Error;
+            report.error = Some(e.to_string());
+        }
+    }
+    report
+}
+
+fn check_editor(cfg: &Config) -> EditorReport {
+    let (editor, command) = match cfg.editor {
+        ValidEditors::Code => ("VS Code", "code"),
+        ValidEditors::Intellij => ("IntelliJ IDEA", "idea"),
+    };
+    // Editors aren't run to ask for their version, since some of them open a window.
+    let path = which(command);
+    EditorReport {
+        status: if path.is_some() {
+            Status::Ok
+        } else {
+            Status::Missing
+        },
+        editor: editor.to_string(),
+        command: command.to_string(),
+        path,
+    }
+}
+
+fn check_container_runtime(runtime: &str) -> ToolReport {
+    let mut report = ToolReport {
+        name: runtime.to_string(),
+        status: Status::Missing,
+        required: false,
+        path: None,
+        version: None,
+        minimum: None,
+        detail: None,
+    };
+    let Some(info) = probe::probe(runtime) else {
+        return report;
+    };
+    report.path = Some(info.path.clone());
+    report.version = info.version.map(|version| version.to_string());
+    let running = Command::new(&info.path)
+        .arg("info")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if running {
+        report.status = Status::Ok;
+    } else {
+        report.status = Status::Warning;
+        report.detail = Some(match runtime {
+            "docker" => "the Docker daemon isn't running or isn't reachable".to_string(),
+            _ => format!("`{} info` failed", runtime),
+        });
+    }
+    report
+}
+
+/// Problems with the entries of `path`, a `PATH` value, and whether it has `bin`, the directory
+/// of projector's toolchain shims, if there are shims in it.
+fn check_path(path: &OsStr, bin: Option<&Path>) -> Vec<PathProblem> {
+    let mut problems = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in env::split_paths(path) {
+        let shown = entry.display().to_string();
+        let problem = if entry.as_os_str().is_empty() {
+            Some("empty entry, which means the current directory".to_string())
+        } else if entry.is_relative() {
+            Some("relative entry, which depends on the current directory".to_string())
+        } else if !seen.insert(entry.clone()) {
+            Some("listed more than once".to_string())
+        } else if !entry.is_dir() {
+            Some("doesn't exist".to_string())
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            problems.push(PathProblem {
+                entry: shown,
+                problem,
+            });
+        }
+    }
+    if let Some(bin) = bin {
+        let has_shims = bin
+            .read_dir()
+            .is_ok_and(|mut entries| entries.next().is_some());
+        if has_shims && !env::split_paths(path).any(|entry| entry == bin) {
+            problems.push(PathProblem {
+                entry: bin.display().to_string(),
+                problem: "projector's toolchains aren't on the PATH; add `eval \"$(projector env)\"` to your shell profile".to_string(),
+            });
+        }
+    }
+    problems
+}
+
+fn print(report: &Report) {
+    println!("Languages");
+    for language in &report.languages {
+        if language.used {
+            println!("  {}", language.language);
+        } else {
+            println!(
+                "  {} (no projects or config for it, so missing tools aren't counted)",
+                language.language
+            );
+        }
+        for tool in &language.tools {
+            let mut line = format!("    {:<9} {}", label(tool.status), tool.name);
+            if let Some(version) = &tool.version {
+                line.push_str(&format!(" {}", version));
+            }
+            if let Some(minimum) = tool
+                .minimum
+                .as_ref()
+                .filter(|_| tool.status == Status::Outdated)
+            {
+                line.push_str(&format!(" (needs {})", minimum));
+            }
+            if let Some(path) = &tool.path {
+                line.push_str(&format!("  {}", path.display()));
+            }
+            if tool.status == Status::Missing && !tool.required {
+                line.push_str("  (optional)");
+            }
+            if let Some(detail) = &tool.detail {
+                line.push_str(&format!("  {}", detail));
+            }
+            println!("{}", line);
+        }
+    }
+
+    println!("\nPinned toolchains");
+    if report.pins.is_empty() {
+        println!(
+            "    {:<9} no pin files here or in parent directories",
+            label(Status::Ok)
+        );
+    }
+    for pin in &report.pins {
+        let mut line = format!(
+            "    {:<9} {} {} ({})",
+            label(pin.status),
+            pin.tool,
+            pin.pinned,
+            pin.file.display()
+        );
+        if let Some(installed) = &pin.installed {
+            line.push_str(&format!("  installed {}", installed));
+        }
+        if let Some(detail) = &pin.detail {
+            line.push_str(&format!("  {}", detail));
+        }
+        if let Some(command) = &pin.install_command {
+            line.push_str(&format!("; install it with `{}`", command));
+        }
+        println!("{}", line);
+    }
+
+    println!("\nConfig");
+    let config_path = report
+        .config
+        .path
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "unknown location".to_string());
+    let defaults = if report.config.exists {
+        ""
+    } else {
+        " (not created yet, using defaults)"
+    };
+    println!(
+        "    {:<9} {}{}",
+        label(report.config.status),
+        config_path,
+        defaults
+    );
+    for error in &report.config.errors {
+        println!("              {}", error);
+    }
+
+    println!("\nDatabase");
+    let database = &report.database;
+    if database.exists {
+        println!(
+            "    {:<9} {} (schema {}, integrity {})",
+            label(database.status),
+            database.path.display(),
+            database
+                .schema_version
+                .map(|version| version.to_string())
+                .unwrap_or_else(|| "?".to_string()),
+            database.integrity.as_deref().unwrap_or("not checked")
+        );
+    } else {
+        println!(
+            "    {:<9} {} (not created yet)",
+            label(database.status),
+            database.path.display()
+        );
+    }
+    if let Some(error) = &database.error {
+        println!("              {}", error);
+    }
+
+    println!("\nEditor");
+    match &report.editor.path {
+        Some(path) => println!(
+            "    {:<9} {}  {}",
+            label(report.editor.status),
+            report.editor.editor,
+            path.display()
+        ),
+        None => println!(
+            "    {:<9} {} (`{}` isn't on the PATH)",
+            label(report.editor.status),
+            report.editor.editor,
+            report.editor.command
+        ),
+    }
+
+    println!("\nContainer runtimes");
+    for runtime in &report.container_runtimes {
+        let mut line = format!("    {:<9} {}", label(runtime.status), runtime.name);
+        if let Some(version) = &runtime.version {
+            line.push_str(&format!(" {}", version));
+        }
+        if let Some(detail) = &runtime.detail {
+            line.push_str(&format!("  {}", detail));
+        }
+        println!("{}", line);
+    }
+
+    println!("\nPATH");
+    if report.path.is_empty() {
+        println!("    {:<9} no problems found", label(Status::Ok));
+    }
+    for problem in &report.path {
+        println!(
+            "    {:<9} {}: {}",
+            label(Status::Warning),
+            problem.entry,
+            problem.problem
+        );
+    }
+
+    println!("\n{}", summary(report.problems));
+}
+
+fn summary(problems: usize) -> String {
+    match problems {
+        0 => "No problems found.".to_string(),
+        1 => "1 problem found.".to_string(),
+        n => format!("{} problems found.", n),
+    }
+}
+
+fn label(status: Status) -> &'static str {
+    match status {
+        Status::Ok => "ok",
+        Status::Missing => "missing",
+        Status::Outdated => "too old",
+        Status::Warning => "warning",
+        Status::Error => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, status: Status, required: bool) -> ToolReport {
+        ToolReport {
+            name: name.to_string(),
+            status,
+            required,
+            path: None,
+            version: None,
+            minimum: None,
+            detail: None,
+        }
+    }
+
+    fn report(languages: Vec<LanguageReport>) -> Report {
+        Report {
+            languages,
+            pins: Vec::new(),
+            config: ConfigReport {
+                status: Status::Ok,
+                path: None,
+                exists: false,
+                errors: Vec::new(),
+            },
+            database: DatabaseReport {
+                status: Status::Ok,
+                path: PathBuf::from("projects.db"),
+                exists: false,
+                schema_version: None,
+                integrity: None,
+                error: None,
+            },
+            editor: EditorReport {
+                status: Status::Ok,
+                editor: "VS Code".to_string(),
+                command: "code".to_string(),
+                path: None,
+            },
+            container_runtimes: vec![tool("docker", Status::Missing, false)],
+            path: Vec::new(),
+            problems: 0,
+        }
+    }
+
+    #[test]
+    fn only_missing_required_tools_are_problems() {
+        let cases = [
+            (Status::Ok, true, false),
+            (Status::Ok, false, false),
+            (Status::Missing, true, true),
+            (Status::Missing, false, false),
+            (Status::Outdated, false, true),
+            (Status::Warning, false, true),
+            (Status::Error, true, true),
+        ];
+        for (status, required, problem) in cases {
+            assert_eq!(is_problem(status, required), problem, "{}", label(status));
+        }
+    }
+
+    #[test]
+    fn missing_tools_count_only_for_used_languages() {
+        let languages = vec![
+            LanguageReport {
+                language: "Rust".to_string(),
+                used: true,
+                tools: vec![
+                    tool("cargo", Status::Missing, true),
+                    tool("rustc", Status::Outdated, true),
+                ],
+            },
+            LanguageReport {
+                language: "Go".to_string(),
+                used: false,
+                tools: vec![tool("go", Status::Missing, true)],
+            },
+        ];
+        let mut report = report(languages);
+        assert_eq!(count_problems(&report), 2);
+
+        report.editor.status = Status::Missing;
+        report.path.push(PathProblem {
+            entry: String::new(),
+            problem: "empty entry".to_string(),
+        });
+        assert_eq!(count_problems(&report), 4);
+    }
+
+    #[test]
+    fn languages_are_used_through_projects_or_config() {
+        let cfg: Config = toml::from_str(
+            "[min_versions.rust]\nrustc = \"1.80\"\n\n[[language_hooks.py.post_scaffold]]\ncommand = \"true\"\n\n[min_versions.cobol]\n",
+        )
+        .unwrap();
+        let used = used_languages(&cfg, &[ProgrammingLanguage::Go]);
+        let expected = [
+            ProgrammingLanguage::Rust,
+            ProgrammingLanguage::Python,
+            ProgrammingLanguage::Go,
+        ];
+        assert_eq!(used, expected.into_iter().collect());
+        assert!(used_languages(&Config::default(), &[]).is_empty());
+    }
+
+    #[test]
+    fn summary_counts_problems() {
+        assert_eq!(summary(0), "No problems found.");
+        assert_eq!(summary(1), "1 problem found.");
+        assert_eq!(summary(3), "3 problems found.");
+    }
+
+    #[test]
+    fn missing_config_is_not_created() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("projector/default-config.toml");
+        let (report, _) = check_config(Some(path.clone()));
+        assert!(report.status == Status::Ok);
+        assert!(!report.exists);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn config_errors_are_reported() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("config.toml");
+        let cases = [
+            ("editor = 3\n", "TOML parse error"),
+            (
+                "[min_versions.rust]\nrustc = \"new\"\n",
+                "Invalid minimum version",
+            ),
+            ("[min_versions.cobol]\n", "Unknown language 'cobol'"),
+            ("download_proxy = \"::\"\n", "Invalid download_proxy"),
+        ];
+        for (contents, error) in cases {
+            fs::write(&path, contents).unwrap();
+            let (report, _) = check_config(Some(path.clone()));
+            assert!(report.status == Status::Error, "{}", contents);
+            assert!(report.exists);
+            assert!(
+                report.errors.iter().any(|e| e.contains(error)),
+                "{:?}",
+                report.errors
+            );
+        }
+        fs::write(&path, "license = \"Apache-2.0\"\n").unwrap();
+        let (report, cfg) = check_config(Some(path));
+        assert!(report.status == Status::Ok);
+        assert_eq!(cfg.license, "Apache-2.0");
+    }
+
+    #[test]
+    fn path_problems() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("dir");
+        let bin = temp.path().join("bin");
+        fs::create_dir_all(&dir).unwrap();
+        fs::create_dir_all(&bin).unwrap();
+        let gone = temp.path().join("gone");
+        let path = env::join_paths([
+            dir.clone(),
+            PathBuf::new(),
+            PathBuf::from("relative"),
+            dir.clone(),
+            gone.clone(),
+        ])
+        .unwrap();
+
+        let problems: Vec<(String, String)> = check_path(&path, Some(&bin))
+            .into_iter()
+            .map(|problem| (problem.entry, problem.problem))
+            .collect();
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems[0].0.is_empty());
+        assert_eq!(problems[1].0, "relative");
+        assert_eq!(problems[2].1, "listed more than once");
+        assert_eq!(
+            problems[3],
+            (gone.display().to_string(), "doesn't exist".to_string())
+        );
+
+        // An empty shim directory doesn't need to be on the PATH; one with shims does.
+        let clean = env::join_paths([dir.clone()]).unwrap();
+        assert!(check_path(&clean, Some(&bin)).is_empty());
+        fs::write(bin.join("go"), "").unwrap();
+        let problems = check_path(&clean, Some(&bin));
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].problem.contains("projector env"));
+        let with_bin = env::join_paths([dir, bin.clone()]).unwrap();
+        assert!(check_path(&with_bin, Some(&bin)).is_empty());
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use color_eyre::eyre::{bail, eyre};
+use directories::ProjectDirs;
+use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::blocking::Client;
+use reqwest::header::RANGE;
+use reqwest::{Proxy, StatusCode};
+use sha2::{Digest, Sha256};
+
+use crate::state::app::Config;
+
+/// Fetches installers, toolchain archives and the indexes listing them, going through the
+/// proxy and mirrors from the config.
+pub struct Downloader {
+    client: Client,
+    mirrors: BTreeMap<String, String>,
+    /// Where unfinished downloads are kept, see [`Downloader::part_path`].
+    cache_dir: PathBuf,
+}
+
+impl Downloader {
+    /// A downloader set up from the user's config, keeping unfinished downloads in projector's
+    /// cache directory.
+    pub fn new() -> color_eyre::Result<Downloader> {
+        let cfg: Config = confy::load("projector", None).unwrap_or_default();
+        let cache_dir = ProjectDirs::from("rs", "", "projector")
+            .map(|dirs| dirs.cache_dir().join("downloads"))
+            .ok_or_else(|| eyre!("Couldn't find a cache directory for projector"))?;
+        Downloader::with_config(&cfg, cache_dir)
+    }
+
+    /// A downloader using `download_proxy` and `download_mirrors` from `cfg`, keeping unfinished
+    /// downloads in `cache_dir`. Without a proxy in `cfg`, the usual
+    /// `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` variables are used. There's no overall timeout,
+    /// since installers can take a while on slow connections.
+    pub fn with_config(cfg: &Config, cache_dir: PathBuf) -> color_eyre::Result<Downloader> {
+        let mut builder = Client::builder()
+            .user_agent(concat!("projector/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(Duration::from_secs(30))
+            .timeout(None);
+        if let Some(proxy) = &cfg.download_proxy {
+            builder = builder.proxy(
+                Proxy::all(proxy)
+                    .map_err(|e| eyre!("Invalid download_proxy '{}': {}", proxy, e))?,
+            );
+        }
+        Ok(Downloader {
+            client: builder.build()?,
+            mirrors: cfg.download_mirrors.clone(),
+            cache_dir,
+        })
+    }
+
+    /// A downloader without mirrors or proxies, for tests against a local server.
+    #[cfg(test)]
+    pub fn for_tests(cache_dir: PathBuf) -> Downloader {
+        Downloader {
+            client: Client::builder().no_proxy().build().unwrap(),
+            mirrors: BTreeMap::new(),
+            cache_dir,
+        }
+    }
+
+    /// `url` with the longest matching prefix in `download_mirrors` replaced by its mirror, see
+    /// [`mirror`].
+    pub fn mirrored(&self, url: &str) -> String {
+        mirror(&self.mirrors, url)
+    }
+
+    /// Fetches a small text document, such as a download index or checksum manifest.
+    pub fn text(&self, url: &str) -> color_eyre::Result<String> {
+        let url = self.mirrored(url);
+        self.client
+            .get(&url)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text())
+            .map_err(|e| eyre!("couldn't fetch {}: {}", url, e))
+    }
+
+    /// Fetches the checksum manifest at `manifest_url`, in `sha256sum` format, and returns the
+    /// SHA-256 it lists for `file_name`, see [`manifest_sha256`].
+    pub fn published_sha256(
+        &self,
+        manifest_url: &str,
+        file_name: &str,
+    ) -> color_eyre::Result<String> {
+        manifest_sha256(&self.text(manifest_url)?, file_name)
+            .ok_or_else(|| eyre!("{} has no checksum for {}", manifest_url, file_name))
+    }
+
+    /// Downloads `url`, or its mirror, to `dest` with a progress bar, checking the file's
+    /// SHA-256 if one is given.
+    ///
+    /// The file is written to a part file in the cache directory, see [`Downloader::part_path`],
+    /// and only moved to `dest` once it's complete and verified. If the connection drops, the
+    /// part file is kept and the next attempt resumes where it stopped; on HTTP errors and
+    /// checksum mismatches it's deleted.
+    pub fn file(&self, url: &str, dest: &Path, sha256: Option<&str>) -> color_eyre::Result<()> {
+        let url = &self.mirrored(url);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let part = self.part_path(url, dest);
+        if let Some(parent) = part.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let client = &self.client;
+        let mut offset = fs::metadata(&part).map(|meta| meta.len()).unwrap_or(0);
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+        let mut response = request
+            .send()
+            .map_err(|e| eyre!("couldn't download {}: {}", url, e))?;
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The part file doesn't belong to what's served now, so start over.
+            fs::remove_file(&part)?;
+            offset = 0;
+            response = client
+                .get(url)
+                .send()
+                .map_err(|e| eyre!("couldn't download {}: {}", url, e))?;
+        }
+        let response = match response.error_for_status() {
+            Ok(response) => response,
+            Err(e) => {
+                remove_part(&part);
+                bail!("couldn't download {}: {}", url, e);
+            }
+        };
+        // Servers without range support send the whole file again.
+        let resumed = offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+        if !resumed {
+            offset = 0;
+        }
+        let mut out = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&part)?;
+
+        let name = dest
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let progress = progress_bar(response.content_length().map(|len| len + offset), name);
+        progress.set_position(offset);
+        if let Err(e) = io::copy(&mut progress.wrap_read(response), &mut out) {
+            progress.abandon();
+            bail!(
+                "downloading {} was interrupted: {}. Run projector again to resume.",
+                url,
+                e
+            );
+        }
+        progress.finish_and_clear();
+        drop(out);
+
+        if let Some(expected) = sha256 {
+            let actual = sha256_file(&part)?;
+            if !actual.eq_ignore_ascii_case(expected) {
+                remove_part(&part);
+                bail!(
+                    "checksum mismatch for {}: expected SHA-256 {}, got {}",
+                    url,
+                    expected,
+                    actual
+                );
+            }
+        }
+        // The cache and `dest` may be on different file systems.
+        if fs::rename(&part, dest).is_err() {
+            fs::copy(&part, dest)?;
+            remove_part(&part);
+        }
+        Ok(())
+    }
+
+    /// Where an unfinished download of `url` to `dest` is kept: in the cache directory, named
+    /// after the file and a hash of the URL so that downloads of different versions don't
+    /// resume each other.
+    pub fn part_path(&self, url: &str, dest: &Path) -> PathBuf {
+        let url_hash: String = Sha256::digest(url.as_bytes())
+            .iter()
+            .take(8)
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        let name = dest
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.cache_dir.join(format!("{}-{}.part", url_hash, name))
+    }
+}
+
+/// `url` with the longest matching prefix in `mirrors` replaced by its mirror, e.g.
+/// `https://nodejs.org/dist` = `https://mirror.example.com/node`.
+fn mirror(mirrors: &BTreeMap<String, String>, url: &str) -> String {
+    mirrors
+        .iter()
+        .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(prefix, mirror)| {
+            format!(
+                "{}{}",
+                mirror.trim_end_matches('/'),
+                &url[prefix.trim_end_matches('/').len()..]
+            )
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// The SHA-256 `manifest` lists for `file_name`. A manifest holding a single hash without a file
+/// name, like rustup's `.sha256` files, is taken to be for `file_name`; otherwise the names must
+/// match.
+fn manifest_sha256(manifest: &str, file_name: &str) -> Option<String> {
+    let entries: Vec<(&str, &str)> = manifest
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let hash = fields.next()?;
+            let name = fields.next().unwrap_or_default();
+            Some((hash, name.trim_start_matches('*')))
+        })
+        .collect();
+    let hash = match entries.as_slice() {
+        [(hash, "")] => Some(*hash),
+        entries => entries
+            .iter()
+            .find(|(_, name)| name.rsplit('/').next() == Some(file_name))
+            .map(|(hash, _)| *hash),
+    };
+    hash.map(str::to_lowercase)
+}
+
+pub fn sha256_file(path: &Path) -> color_eyre::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// A progress bar on stderr, hidden when it isn't a terminal. Without a length, shows a spinner
+/// with the bytes received so far.
+fn progress_bar(len: Option<u64>, name: String) -> ProgressBar {
+    let (progress, template) = match len {
+        Some(len) => (
+            ProgressBar::new(len),
+            "{msg} [{bar:30}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+        ),
+        None => (
+            ProgressBar::no_length(),
+            "{msg} {spinner} {bytes} ({bytes_per_sec})",
+        ),
+    };
+    if let Ok(style) = ProgressStyle::with_template(template) {
+        progress.set_style(style.progress_chars("=> "));
+    }
+    progress.set_message(name);
+    progress
+}
+
+fn remove_part(part: &Path) {
+    if let Err(e) = fs::remove_file(part) {
+        if e.kind() != io::ErrorKind::NotFound {
+            eprintln!("Warning: Failed to remove {}: {}", part.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::install::test_server::{Route, Server};
+
+    /// 100 kB that don't repeat at any short period, so a misplaced resume shows.
+    fn body() -> Vec<u8> {
+        (0..100_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn sha256(bytes: &[u8]) -> String {
+        Sha256::digest(bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// A downloader with its cache in `temp`, the URL of `route` on a fresh server, the server
+    /// and the destination.
+    fn setup(temp: &Path, route: Route) -> (Downloader, String, Server, PathBuf) {
+        let url_target = route.target.clone();
+        let server = Server::start(vec![route]);
+        let url = server.url(&url_target);
+        (
+            Downloader::for_tests(temp.join("cache")),
+            url,
+            server,
+            temp.join("out/file.tar.gz"),
+        )
+    }
+
+    fn write_part(downloader: &Downloader, url: &str, dest: &Path, contents: &[u8]) -> PathBuf {
+        let part = downloader.part_path(url, dest);
+        fs::create_dir_all(part.parent().unwrap()).unwrap();
+        fs::write(&part, contents).unwrap();
+        part
+    }
+
+    #[test]
+    fn downloads_and_verifies() {
+        let temp = tempfile::tempdir().unwrap();
+        let body = body();
+        let (downloader, url, server, dest) = setup(temp.path(), Route::new("/f", body.clone()));
+
+        downloader.file(&url, &dest, Some(&sha256(&body))).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert!(!downloader.part_path(&url, &dest).exists());
+        assert_eq!(server.ranges(), vec![None]);
+    }
+
+    #[test]
+    fn downloads_from_the_mirror() {
+        let temp = tempfile::tempdir().unwrap();
+        let body = body();
+        let server = Server::start(vec![Route::new("/mirror/go/f.tar.gz", body.clone())]);
+        let mirrors =
+            BTreeMap::from([("https://go.dev/dl".to_string(), server.url("/mirror/go/"))]);
+        let downloader = Downloader {
+            mirrors,
+            ..Downloader::for_tests(temp.path().join("cache"))
+        };
+        let dest = temp.path().join("f.tar.gz");
+
+        downloader
+            .file("https://go.dev/dl/f.tar.gz", &dest, Some(&sha256(&body)))
+            .unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), body);
+    }
+
+    #[test]
+    fn resumes_a_part_file_with_a_range_request() {
+        let temp = tempfile::tempdir().unwrap();
+        let body = body();
+        let (downloader, url, server, dest) = setup(temp.path(), Route::new("/f", body.clone()));
+        let part = write_part(&downloader, &url, &dest, &body[..40_000]);
+
+        downloader.file(&url, &dest, Some(&sha256(&body))).unwrap();
+        assert_eq!(server.ranges(), vec![Some("bytes=40000-".to_string())]);
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert!(!part.exists());
+    }
+
+    #[test]
+    fn starts_over_when_the_range_is_not_satisfiable() {
+        let temp = tempfile::tempdir().unwrap();
+        let body = body();
+        let (downloader, url, server, dest) = setup(temp.path(), Route::new("/f", body.clone()));
+        // Left over from a bigger file served at the same URL before.
+        write_part(&downloader, &url, &dest, &[7; 120_000]);
+
+        downloader.file(&url, &dest, Some(&sha256(&body))).unwrap();
+        assert_eq!(
+            server.ranges(),
+            vec![Some("bytes=120000-".to_string()), None]
+        );
+        assert_eq!(fs::read(&dest).unwrap(), body);
+    }
+
+    #[test]
+    fn replaces_the_part_file_when_the_server_sends_everything() {
+        let temp = tempfile::tempdir().unwrap();
+        let body = body();
+        let route = Route::new("/f", body.clone()).without_ranges();
+        let (downloader, url, server, dest) = setup(temp.path(), route);
+        write_part(&downloader, &url, &dest, &body[..40_000]);
+
+        downloader.file(&url, &dest, Some(&sha256(&body))).unwrap();
+        assert_eq!(server.ranges(), vec![Some("bytes=40000-".to_string())]);
+        assert_eq!(fs::read(&dest).unwrap(), body);
+    }
+
+    #[test]
+    fn checksum_mismatch_removes_the_part_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let (downloader, url, _server, dest) = setup(temp.path(), Route::new("/f", body()));
+
+        let error = downloader
+            .file(&url, &dest, Some(&"0".repeat(64)))
+            .unwrap_err();
+        assert!(error.to_string().starts_with("checksum mismatch for"));
+        assert!(!downloader.part_path(&url, &dest).exists());
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn http_errors_remove_the_part_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let (downloader, url, _server, dest) = setup(temp.path(), Route::new("/f", body()));
+        let missing = format!("{}-missing", url);
+        let part = write_part(&downloader, &missing, &dest, b"old");
+
+        let error = downloader.file(&missing, &dest, None).unwrap_err();
+        assert!(error.to_string().contains("404"));
+        assert!(!part.exists());
+        assert!(!dest.exists());
+    }
+
+    /// `/dev/shm` is usually a tmpfs, so renaming from it into the temp directory fails and the
+    /// file has to be copied.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn copies_across_file_systems() {
+        use std::os::unix::fs::MetadataExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let Ok(shm) = tempfile::tempdir_in("/dev/shm") else {
+            return;
+        };
+        let device = |path: &Path| fs::metadata(path).unwrap().dev();
+        if device(temp.path()) == device(shm.path()) {
+            return;
+        }
+        let body = body();
+        let server = Server::start(vec![Route::new("/f", body.clone())]);
+        let url = server.url("/f");
+        let downloader = Downloader::for_tests(shm.path().join("cache"));
+        let dest = temp.path().join("file.tar.gz");
+
+        downloader.file(&url, &dest, Some(&sha256(&body))).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert!(!downloader.part_path(&url, &dest).exists());
+    }
+
+    #[test]
+    fn manifest_sha256_matches_file_names() {
+        let manifest = "AAA  go1.22.1.linux-amd64.tar.gz\nbbb *dist/go1.22.1.darwin-arm64.tar.gz\n";
+        assert_eq!(
+            manifest_sha256(manifest, "go1.22.1.linux-amd64.tar.gz").as_deref(),
+            Some("aaa")
+        );
+        assert_eq!(
+            manifest_sha256(manifest, "go1.22.1.darwin-arm64.tar.gz").as_deref(),
+            Some("bbb")
+        );
+        assert_eq!(
+            manifest_sha256(manifest, "go1.22.1.windows-amd64.zip"),
+            None
+        );
+    }
+
+    #[test]
+    fn manifest_sha256_takes_a_lone_hash_only_without_a_name() {
+        assert_eq!(
+            manifest_sha256("abc123\n", "rustup-init").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            manifest_sha256("abc123  other-file.tar.gz\n", "rustup-init"),
+            None
+        );
+    }
+}
@@ -0,0 +1,334 @@
+use std::env;
+
+use chrono::Utc;
+use color_eyre::eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+
+use package_manager::PackageManager;
+use plan::{Action, InstallPlan};
+
+use crate::database::manage::{get_installs, setup_database};
+use crate::database::model::InstallRecord;
+use crate::probe;
+use crate::scaffold::ScaffoldOptions;
+use crate::state::app::Config;
+use crate::ui::prompt::Prompter;
+
+pub mod download;
+pub mod os_release;
+pub mod package_manager;
+pub mod plan;
+#[cfg(test)]
+mod test_server;
+pub mod toolchain;
+
+/// A tool projector can install with the system package manager. Each manager maps it to its
+/// own package names, see [`PackageManager::packages`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// C and C++ compilers.
+    Gcc,
+    Make,
+    /// Python 3 with venv and pip.
+    Python3,
+    /// Visual Studio Code.
+    Code,
+    Go,
+    /// Node.js with npm.
+    Node,
+}
+
+impl Tool {
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Gcc => "GCC",
+            Tool::Make => "Make",
+            Tool::Python3 => "Python 3",
+            Tool::Code => "VS Code",
+            Tool::Go => "Go",
+            Tool::Node => "Node.js",
+        }
+    }
+
+    /// Lowercase id used in the install history and `projector tools remove`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Tool::Gcc => "gcc",
+            Tool::Make => "make",
+            Tool::Python3 => "python3",
+            Tool::Code => "code",
+            Tool::Go => "go",
+            Tool::Node => "node",
+        }
+    }
+}
+
+/// Where [`offer`] installs missing tools from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallMode {
+    /// The system package manager.
+    System,
+    /// Official builds unpacked into projector's prefix, see [`toolchain`].
+    Local,
+}
+
+/// Shows `plan`, then runs it if the user agrees. With `--dry-run` the plan is only shown, and
+/// if privileged installs are turned off in the settings, it's shown as instructions to follow
+/// by hand. Returns whether the plan ran and succeeded; failures are reported here.
+///
+/// `--yes` declines installs, since they're a side effect of what the user asked for, but
+/// accepts removals, which were asked for directly. Every plan that runs is recorded in the
+/// install history.
+pub fn confirm_and_run(
+    plan: &InstallPlan,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<bool> {
+    run_plan(plan, plan.action == Action::Remove, opts, prompter)
+}
+
+/// [`confirm_and_run`], with `default` as the answer `--yes` gives.
+fn run_plan(
+    plan: &InstallPlan,
+    default: bool,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<bool> {
+    prompter.suspend();
+    plan.print();
+    if opts.dry_run {
+        println!("Dry run, nothing was changed.");
+        return Ok(false);
+    }
+    let cfg: Config = confy::load("projector", None).unwrap_or_default();
+    if plan.privileged() && !cfg.allow_privileged_installs {
+        println!("Privileged installs are turned off in the settings. Run the steps above yourself, then run projector again.");
+        return Ok(false);
+    }
+    let question = match plan.action {
+        Action::Install => format!("Install {} as planned? (y/n)", plan.what),
+        Action::Remove => format!("Remove {} as planned? (y/n)", plan.what),
+    };
+    if !opts.confirm(prompter, None, &question, default)? {
+        return Ok(false);
+    }
+    let result = plan.execute();
+    probe::forget_all();
+    if let Err(e) = record(plan, result.is_ok()) {
+        eprintln!(
+            "Warning: Failed to record this in the install history: {}",
+            e
+        );
+    }
+    match result {
+        Ok(()) => Ok(true),
+        Err(e) => {
+            let doing = match plan.action {
+                Action::Install => "Installing",
+                Action::Remove => "Removing",
+            };
+            eprintln!("Error: {} {} failed: {}", doing, plan.what, e);
+            Ok(false)
+        }
+    }
+}
+
+/// Offers to install a missing `tool`, for a scaffolder that can't go on without it. Returns
+/// whether it got installed. Declined by default with `--yes`.
+///
+/// Uses the package manager, unless the install mode is `local` or the package manager can't
+/// be used (there's none, or it needs root and privileged installs are off); then toolchains
+/// are installed from their official builds into projector's prefix instead, and put on the
+/// PATH for the rest of this run.
+pub fn offer(
+    tool: Tool,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<bool> {
+    prompter.suspend();
+    println!("{} is not installed.", tool.name());
+    let cfg: Config = confy::load("projector", None).unwrap_or_default();
+    let local = toolchain::for_tool(tool);
+    if cfg.install_mode == InstallMode::System || local.is_none() {
+        match InstallPlan::packages(&[tool]) {
+            Ok(plan) if !plan.privileged() || cfg.allow_privileged_installs || local.is_none() => {
+                return confirm_and_run(&plan, opts, prompter);
+            }
+            Ok(_) => println!(
+                "Privileged installs are turned off, so projector will install {} for this user only.",
+                tool.name()
+            ),
+            Err(e) if local.is_none() => {
+                eprintln!("Error: {}", e);
+                return Ok(false);
+            }
+            Err(_) => println!(
+                "No package manager here can install {}, so projector will install it for this user only.",
+                tool.name()
+            ),
+        }
+    }
+    let Some(name) = local else {
+        return Ok(false);
+    };
+    install_toolchain(name, None, false, opts, prompter)
+}
+
+/// Installs the official build of toolchain `name` into projector's prefix, see [`toolchain`].
+/// Returns whether it got installed. `requested` is whether the user asked for it directly,
+/// which makes `--yes` accept the install.
+pub fn install_toolchain(
+    name: &str,
+    version: Option<&str>,
+    requested: bool,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<bool> {
+    let plan = match toolchain::resolve(name, version).and_then(|release| toolchain::plan(&release))
+    {
+        Ok(plan) => plan,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Ok(false);
+        }
+    };
+    if !run_plan(&plan, requested, opts, prompter)? {
+        return Ok(false);
+    }
+    let bin = toolchain::bin_dir()?;
+    let on_path =
+        env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|entry| entry == bin));
+    toolchain::activate()?;
+    if !on_path {
+        println!("To use it in your shell, add projector's toolchains to the PATH:");
+        println!("    eval \"$(projector env)\"");
+    }
+    Ok(true)
+}
+
+/// Prints every install and removal projector has run, oldest first.
+pub fn history() -> color_eyre::Result<()> {
+    let conn = setup_database()?;
+    let records = get_installs(&conn)?;
+    if records.is_empty() {
+        println!("Projector hasn't installed anything yet.");
+        return Ok(());
+    }
+    println!(
+        "{:<16} {:<7} {:<12} {:<16} {:<9} {:<6} PACKAGES",
+        "DATE (UTC)", "ACTION", "TOOL", "VERSION", "METHOD", "RESULT"
+    );
+    for record in records {
+        println!(
+            "{:<16} {:<7} {:<12} {:<16} {:<9} {:<6} {}",
+            record.performed_on.format("%Y-%m-%d %H:%M"),
+            record.action,
+            record.tool,
+            record.version.as_deref().unwrap_or("-"),
+            record.method,
+            if record.success { "ok" } else { "failed" },
+            if record.packages.is_empty() {
+                "-"
+            } else {
+                &record.packages
+            }
+        );
+        println!("    $ {}", record.command);
+    }
+    Ok(())
+}
+
+/// Undoes the latest install of `tool` that projector did with a package manager, removing only
+/// the packages that install added, or deletes the toolchain it installed locally.
+pub fn remove(
+    tool: &str,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let conn = setup_database()?;
+    let records = get_installs(&conn)?;
+    // The latest action that worked tells whether the tool is still installed.
+    let record = match records
+        .iter()
+        .rev()
+        .find(|record| record.tool == tool && record.success)
+    {
+        Some(record) if record.action == Action::Install.id() => record,
+        Some(_) => bail!("{} was already removed by projector", tool),
+        None => bail!(
+            "Projector has no record of installing {}; see `projector tools history`",
+            tool
+        ),
+    };
+    if record.method == "local" {
+        let version = record
+            .packages
+            .strip_prefix(&format!("{}-", tool))
+            .ok_or_else(|| {
+                eyre!(
+                    "Unexpected install record for {}: {}",
+                    tool,
+                    record.packages
+                )
+            })?;
+        let plan = toolchain::removal(tool, version)?;
+        confirm_and_run(&plan, opts, prompter)?;
+        return Ok(());
+    }
+    let manager = PackageManager::from_id(&record.method).ok_or_else(|| {
+        eyre!(
+            "{} was installed with {}, which projector can't undo. Remove it by hand.",
+            tool,
+            record.method
+        )
+    })?;
+    let packages: Vec<&str> = record.packages.split_whitespace().collect();
+    if packages.is_empty() {
+        bail!(
+            "{}'s packages were already installed before projector installed it, so there's nothing to remove",
+            tool
+        );
+    }
+    let plan = InstallPlan::removal(tool, manager, &packages)?;
+    confirm_and_run(&plan, opts, prompter)?;
+    Ok(())
+}
+
+/// Adds a row per tool in `plan` to the install history.
+fn record(plan: &InstallPlan, success: bool) -> color_eyre::Result<()> {
+    let conn = setup_database()?;
+    let command = plan.command_line();
+    for (tool, packages) in &plan.tools {
+        let version = match plan.action {
+            Action::Install if success => plan.version.clone().or_else(|| installed_version(tool)),
+            _ => None,
+        };
+        let mut record = InstallRecord {
+            id: 0,
+            action: plan.action.id().to_string(),
+            tool: tool.clone(),
+            version,
+            method: plan.method.clone(),
+            packages: packages.join(" "),
+            command: command.clone(),
+            success,
+            performed_on: Utc::now().naive_utc(),
+        };
+        record.insert(&conn)?;
+    }
+    Ok(())
+}
+
+/// The version `tool` reports, or `None` if it isn't on the PATH yet, e.g. until the shell is
+/// restarted.
+fn installed_version(tool: &str) -> Option<String> {
+    let binary = match tool {
+        "rust" => "rustc",
+        "visual-studio" => return None,
+        tool => tool,
+    };
+    probe::probe(binary)?
+        .version
+        .map(|version| version.to_string())
+}
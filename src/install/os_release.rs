@@ -0,0 +1,147 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The fields of os-release(5) used to pick a package manager.
+#[derive(Debug, Default, Clone)]
+pub struct OsRelease {
+    /// Lowercase distro identifier, e.g. `ubuntu` or `opensuse-tumbleweed`.
+    pub id: String,
+    /// Distros this one derives from, closest first, e.g. `["ubuntu", "debian"]` for Mint.
+    pub id_like: Vec<String>,
+    /// Human-readable name, e.g. `Fedora Linux 41 (Workstation Edition)`.
+    pub pretty_name: String,
+}
+
+impl OsRelease {
+    /// Reads `/etc/os-release`, falling back to `/usr/lib/os-release`. `PROJECTOR_OS_RELEASE`
+    /// points at a different file, for trying out other distros.
+    pub fn load() -> Option<OsRelease> {
+        let paths = match env::var_os("PROJECTOR_OS_RELEASE") {
+            Some(path) => vec![PathBuf::from(path)],
+            None => vec![
+                PathBuf::from("/etc/os-release"),
+                PathBuf::from("/usr/lib/os-release"),
+            ],
+        };
+        OsRelease::read(&paths)
+    }
+
+    /// Parses the first of `paths` that can be read.
+    fn read(paths: &[PathBuf]) -> Option<OsRelease> {
+        paths
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())
+            .map(|contents| OsRelease::parse(&contents))
+    }
+
+    /// Parses the `KEY=value` lines of an os-release file. Values may be quoted; unknown keys,
+    /// comments and malformed lines are ignored.
+    pub fn parse(contents: &str) -> OsRelease {
+        let mut release = OsRelease::default();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = unquote(value.trim());
+            match key.trim() {
+                "ID" => release.id = value.to_lowercase(),
+                "ID_LIKE" => {
+                    release.id_like = value
+                        .split_whitespace()
+                        .map(|id| id.to_lowercase())
+                        .collect()
+                }
+                "PRETTY_NAME" => release.pretty_name = value,
+                _ => {}
+            }
+        }
+        release
+    }
+
+    /// The distro's own ID followed by the ones it's like.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.id.as_str()).chain(self.id_like.iter().map(String::as_str))
+    }
+
+    /// A name to show the user: `PRETTY_NAME`, or the ID if there's none.
+    pub fn display_name(&self) -> &str {
+        if self.pretty_name.is_empty() {
+            &self.id
+        } else {
+            &self.pretty_name
+        }
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+    let value = if quoted {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    };
+    // Double-quoted values may escape `"`, `\`, `$` and backticks.
+    let mut unquoted = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(escaped) = chars.next() {
+                unquoted.push(escaped);
+            }
+        } else {
+            unquoted.push(c);
+        }
+    }
+    unquoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_unquotes_values_and_skips_the_rest() {
+        let release = OsRelease::parse(
+            "# comment\n\
+             NAME=\"Linux Mint\"\n\
+             ID=LinuxMint\n\
+             ID_LIKE='ubuntu debian'\n\
+             PRETTY_NAME=\"Linux Mint 22 \\\"Wilma\\\"\"\n\
+             not a key value line\n",
+        );
+        assert_eq!(release.id, "linuxmint");
+        assert_eq!(release.id_like, ["ubuntu", "debian"]);
+        assert_eq!(release.pretty_name, "Linux Mint 22 \"Wilma\"");
+        assert_eq!(release.display_name(), "Linux Mint 22 \"Wilma\"");
+        assert_eq!(
+            release.ids().collect::<Vec<_>>(),
+            ["linuxmint", "ubuntu", "debian"]
+        );
+    }
+
+    #[test]
+    fn display_name_falls_back_to_the_id() {
+        let release = OsRelease::parse("ID=alpine\n");
+        assert!(release.id_like.is_empty());
+        assert_eq!(release.display_name(), "alpine");
+    }
+
+    #[test]
+    fn read_falls_back_to_the_next_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let fallback = dir.path().join("os-release");
+        fs::write(&fallback, "ID=fedora\nPRETTY_NAME=\"Fedora Linux 41\"\n").unwrap();
+
+        let release = OsRelease::read(&[dir.path().join("missing"), fallback]).unwrap();
+        assert_eq!(release.id, "fedora");
+        assert_eq!(release.pretty_name, "Fedora Linux 41");
+        assert!(OsRelease::read(&[dir.path().join("missing")]).is_none());
+    }
+}
@@ -0,0 +1,365 @@
+use std::env;
+use std::ffi::OsStr;
+use std::fmt;
+use std::process::{Command, Stdio};
+
+use crate::install::os_release::OsRelease;
+use crate::install::Tool;
+use crate::util::{is_on_path, which_in};
+
+/// A system package manager projector knows how to drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Apt,
+    Dnf,
+    Pacman,
+    Zypper,
+    Apk,
+    Xbps,
+    Brew,
+    Nix,
+}
+
+impl PackageManager {
+    pub const ALL: [PackageManager; 8] = [
+        PackageManager::Apt,
+        PackageManager::Dnf,
+        PackageManager::Pacman,
+        PackageManager::Zypper,
+        PackageManager::Apk,
+        PackageManager::Xbps,
+        PackageManager::Brew,
+        PackageManager::Nix,
+    ];
+
+    /// Picks the package manager for this machine: Homebrew on macOS, otherwise the one
+    /// belonging to the distro in os-release, or failing that the first one found on the PATH.
+    /// A manager is only returned if its binary is actually there.
+    pub fn detect() -> Option<PackageManager> {
+        if cfg!(target_os = "macos") {
+            return PackageManager::Brew
+                .is_available()
+                .then_some(PackageManager::Brew);
+        }
+        let path = env::var_os("PATH").unwrap_or_default();
+        PackageManager::detect_in(OsRelease::load().as_ref(), &path)
+    }
+
+    /// The Linux part of [`detect`](PackageManager::detect), looking for binaries in `path`.
+    fn detect_in(release: Option<&OsRelease>, path: &OsStr) -> Option<PackageManager> {
+        let available = |manager: &PackageManager| which_in(manager.binary(), path).is_some();
+        release
+            .and_then(PackageManager::for_distro)
+            .filter(available)
+            .or_else(|| PackageManager::ALL.into_iter().find(available))
+    }
+
+    /// The native package manager of `release`, going by its `ID` and then `ID_LIKE`.
+    pub fn for_distro(release: &OsRelease) -> Option<PackageManager> {
+        release.ids().find_map(|id| match id {
+            "debian" | "ubuntu" | "linuxmint" | "pop" | "raspbian" => Some(PackageManager::Apt),
+            "fedora" | "rhel" | "centos" | "rocky" | "almalinux" | "amzn" => {
+                Some(PackageManager::Dnf)
+            }
+            "arch" | "manjaro" | "endeavouros" => Some(PackageManager::Pacman),
+            "suse" | "opensuse" | "sles" => Some(PackageManager::Zypper),
+            id if id.starts_with("opensuse") => Some(PackageManager::Zypper),
+            "alpine" => Some(PackageManager::Apk),
+            "void" => Some(PackageManager::Xbps),
+            "nixos" => Some(PackageManager::Nix),
+            _ => None,
+        })
+    }
+
+    /// Short lowercase name, as stored in the install history.
+    pub fn id(self) -> &'static str {
+        match self {
+            PackageManager::Apt => "apt",
+            PackageManager::Dnf => "dnf",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Zypper => "zypper",
+            PackageManager::Apk => "apk",
+            PackageManager::Xbps => "xbps",
+            PackageManager::Brew => "brew",
+            PackageManager::Nix => "nix",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<PackageManager> {
+        PackageManager::ALL
+            .into_iter()
+            .find(|manager| manager.id() == id)
+    }
+
+    /// The binary that installs packages.
+    pub fn binary(self) -> &'static str {
+        match self {
+            PackageManager::Apt => "apt-get",
+            PackageManager::Dnf => "dnf",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Zypper => "zypper",
+            PackageManager::Apk => "apk",
+            PackageManager::Xbps => "xbps-install",
+            PackageManager::Brew => "brew",
+            PackageManager::Nix => "nix",
+        }
+    }
+
+    pub fn is_available(self) -> bool {
+        is_on_path(self.binary())
+    }
+
+    /// Whether installing system-wide needs root. Homebrew and Nix profiles are per user.
+    pub fn needs_root(self) -> bool {
+        !matches!(self, PackageManager::Brew | PackageManager::Nix)
+    }
+
+    /// The packages providing `tool`, or `None` if this manager doesn't carry it.
+    pub fn packages(self, tool: Tool) -> Option<&'static [&'static str]> {
+        use PackageManager::*;
+        let packages: &[&str] = match (tool, self) {
+            (Tool::Gcc, Apt) => &["gcc", "g++"],
+            (Tool::Gcc, Dnf | Zypper) => &["gcc", "gcc-c++"],
+            (Tool::Gcc, Apk) => &["gcc", "g++", "musl-dev"],
+            (Tool::Gcc, Pacman | Xbps | Brew | Nix) => &["gcc"],
+            (Tool::Make, Nix) => &["gnumake"],
+            (Tool::Make, _) => &["make"],
+            (Tool::Python3, Apt) => &["python3", "python3-venv", "python3-pip"],
+            (Tool::Python3, Dnf | Zypper | Xbps) => &["python3", "python3-pip"],
+            (Tool::Python3, Pacman) => &["python", "python-pip"],
+            (Tool::Python3, Apk) => &["python3", "py3-pip"],
+            (Tool::Python3, Brew) => &["python"],
+            (Tool::Python3, Nix) => &["python3"],
+            // VS Code isn't in Alpine's repositories. On apt, dnf and zypper it needs
+            // Microsoft's repository set up first.
+            (Tool::Code, Apk) => return None,
+            (Tool::Code, Xbps | Nix) => &["vscode"],
+            (Tool::Code, Brew) => &["visual-studio-code"],
+            (Tool::Code, _) => &["code"],
+            (Tool::Go, Apt) => &["golang-go"],
+            (Tool::Go, Dnf) => &["golang"],
+            (Tool::Go, _) => &["go"],
+            (Tool::Node, Xbps | Nix) => &["nodejs"],
+            (Tool::Node, Brew) => &["node"],
+            (Tool::Node, _) => &["nodejs", "npm"],
+        };
+        Some(packages)
+    }
+
+    /// Whether `package` is already installed. `false` when it can't be told, e.g. with Nix.
+    pub fn is_installed(self, package: &str) -> bool {
+        let (program, args): (&str, &[&str]) = match self {
+            PackageManager::Apt => ("dpkg", &["-s"]),
+            PackageManager::Dnf | PackageManager::Zypper => ("rpm", &["-q"]),
+            PackageManager::Pacman => ("pacman", &["-Q"]),
+            PackageManager::Apk => ("apk", &["info", "-e"]),
+            PackageManager::Xbps => ("xbps-query", &[]),
+            PackageManager::Brew => ("brew", &["list"]),
+            PackageManager::Nix => return false,
+        };
+        Command::new(program)
+            .args(args)
+            .arg(package)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    /// The commands installing `packages` non-interactively, without `sudo`.
+    pub fn install_commands(self, packages: &[&str]) -> Vec<Vec<String>> {
+        let command = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let with_packages = |args: &[&str]| {
+            let mut command = command(args);
+            command.extend(packages.iter().map(|package| package.to_string()));
+            command
+        };
+        match self {
+            // apt's package lists are often stale or missing, e.g. in fresh containers.
+            PackageManager::Apt => vec![
+                command(&["apt-get", "update"]),
+                with_packages(&["apt-get", "install", "-y"]),
+            ],
+            PackageManager::Dnf => vec![with_packages(&["dnf", "install", "-y"])],
+            PackageManager::Pacman => {
+                vec![with_packages(&["pacman", "-S", "--needed", "--noconfirm"])]
+            }
+            PackageManager::Zypper => {
+                vec![with_packages(&["zypper", "--non-interactive", "install"])]
+            }
+            PackageManager::Apk => vec![with_packages(&["apk", "add"])],
+            PackageManager::Xbps => vec![with_packages(&["xbps-install", "-y"])],
+            PackageManager::Brew => vec![with_packages(&["brew", "install"])],
+            PackageManager::Nix => {
+                let mut install = command(&["nix", "profile", "install"]);
+                install.extend(
+                    packages
+                        .iter()
+                        .map(|package| format!("nixpkgs#{}", package)),
+                );
+                vec![install]
+            }
+        }
+    }
+
+    /// The commands removing `packages` non-interactively, without `sudo`.
+    pub fn remove_commands(self, packages: &[&str]) -> Vec<Vec<String>> {
+        let args: &[&str] = match self {
+            PackageManager::Apt => &["apt-get", "remove", "-y"],
+            PackageManager::Dnf => &["dnf", "remove", "-y"],
+            PackageManager::Pacman => &["pacman", "-R", "--noconfirm"],
+            PackageManager::Zypper => &["zypper", "--non-interactive", "remove"],
+            PackageManager::Apk => &["apk", "del"],
+            PackageManager::Xbps => &["xbps-remove", "-y"],
+            PackageManager::Brew => &["brew", "uninstall"],
+            PackageManager::Nix => &["nix", "profile", "remove"],
+        };
+        let mut command: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        command.extend(packages.iter().map(|package| package.to_string()));
+        vec![command]
+    }
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PackageManager::Apt => "apt",
+            PackageManager::Dnf => "dnf",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Zypper => "zypper",
+            PackageManager::Apk => "apk",
+            PackageManager::Xbps => "xbps",
+            PackageManager::Brew => "Homebrew",
+            PackageManager::Nix => "Nix",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+
+    fn manager_for(os_release: &str) -> Option<PackageManager> {
+        PackageManager::for_distro(&OsRelease::parse(os_release))
+    }
+
+    /// Puts an executable called `name` in `dir`, standing in for a package manager.
+    fn stub(dir: &Path, name: &str) {
+        let path = dir.join(name);
+        fs::write(&path, "#!/bin/sh\nexit 0\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    #[test]
+    fn for_distro_uses_id_then_id_like() {
+        assert_eq!(manager_for("ID=ubuntu"), Some(PackageManager::Apt));
+        assert_eq!(manager_for("ID=\"rocky\""), Some(PackageManager::Dnf));
+        assert_eq!(
+            manager_for("ID=opensuse-tumbleweed"),
+            Some(PackageManager::Zypper)
+        );
+        assert_eq!(manager_for("ID=void"), Some(PackageManager::Xbps));
+        assert_eq!(
+            manager_for("ID=garuda\nID_LIKE=\"arch\""),
+            Some(PackageManager::Pacman)
+        );
+        assert_eq!(
+            manager_for("ID=elementary\nID_LIKE=\"ubuntu debian\""),
+            Some(PackageManager::Apt)
+        );
+        assert_eq!(manager_for("ID=gentoo"), None);
+    }
+
+    #[test]
+    fn detect_prefers_the_distro_manager_when_installed() {
+        let bin = tempfile::tempdir().unwrap();
+        stub(bin.path(), "apt-get");
+        stub(bin.path(), "dnf");
+        let path = bin.path().as_os_str();
+
+        let fedora = OsRelease::parse("ID=fedora\n");
+        assert_eq!(
+            PackageManager::detect_in(Some(&fedora), path),
+            Some(PackageManager::Dnf)
+        );
+        // The distro's own manager is missing, so the first one found is used.
+        let arch = OsRelease::parse("ID=arch\n");
+        assert_eq!(
+            PackageManager::detect_in(Some(&arch), path),
+            Some(PackageManager::Apt)
+        );
+        assert_eq!(
+            PackageManager::detect_in(None, path),
+            Some(PackageManager::Apt)
+        );
+
+        let empty = tempfile::tempdir().unwrap();
+        assert_eq!(
+            PackageManager::detect_in(Some(&fedora), empty.path().as_os_str()),
+            None
+        );
+    }
+
+    #[test]
+    fn install_commands_for_each_manager() {
+        let expected: [(PackageManager, &[&[&str]]); 8] = [
+            (
+                PackageManager::Apt,
+                &[
+                    &["apt-get", "update"],
+                    &["apt-get", "install", "-y", "golang-go"],
+                ],
+            ),
+            (PackageManager::Dnf, &[&["dnf", "install", "-y", "golang"]]),
+            (
+                PackageManager::Pacman,
+                &[&["pacman", "-S", "--needed", "--noconfirm", "go"]],
+            ),
+            (
+                PackageManager::Zypper,
+                &[&["zypper", "--non-interactive", "install", "go"]],
+            ),
+            (PackageManager::Apk, &[&["apk", "add", "go"]]),
+            (PackageManager::Xbps, &[&["xbps-install", "-y", "go"]]),
+            (PackageManager::Brew, &[&["brew", "install", "go"]]),
+            (
+                PackageManager::Nix,
+                &[&["nix", "profile", "install", "nixpkgs#go"]],
+            ),
+        ];
+        for (manager, commands) in expected {
+            let packages = manager.packages(Tool::Go).unwrap();
+            assert_eq!(manager.install_commands(packages), commands, "{}", manager);
+        }
+    }
+
+    #[test]
+    fn packages_differ_per_manager() {
+        assert_eq!(
+            PackageManager::Apt.packages(Tool::Python3),
+            Some(&["python3", "python3-venv", "python3-pip"][..])
+        );
+        assert_eq!(
+            PackageManager::Pacman.packages(Tool::Python3),
+            Some(&["python", "python-pip"][..])
+        );
+        assert_eq!(
+            PackageManager::Nix.packages(Tool::Make),
+            Some(&["gnumake"][..])
+        );
+        assert_eq!(PackageManager::Apk.packages(Tool::Code), None);
+        assert_eq!(
+            PackageManager::Apt.remove_commands(&["nodejs", "npm"]),
+            [["apt-get", "remove", "-y", "nodejs", "npm"]]
+        );
+    }
+}
@@ -0,0 +1,651 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::hash::{BuildHasher, RandomState};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::time::SystemTime;
+
+use color_eyre::eyre::{bail, eyre};
+
+use crate::install::download::Downloader;
+use crate::install::os_release::OsRelease;
+use crate::install::package_manager::PackageManager;
+use crate::install::Tool;
+use crate::util::is_on_path;
+
+/// Everything an installation or removal will do, worked out before anything runs so it can be
+/// shown to the user first: which packages, which URLs get downloaded, and which commands run
+/// with root.
+pub struct InstallPlan {
+    pub action: Action,
+    /// What gets installed, e.g. `GCC, Make`.
+    pub what: String,
+    /// Tool ids with the packages each one adds or removes, for the install history.
+    pub tools: Vec<(String, Vec<String>)>,
+    /// The package manager doing the work, if any.
+    pub manager: Option<PackageManager>,
+    /// How the tools get installed, for the install history: the package manager's id, `local`
+    /// for toolchains in projector's own prefix, or `installer` otherwise.
+    pub method: String,
+    /// The version being installed, when it's known before installing.
+    pub version: Option<String>,
+    pub steps: Vec<InstallStep>,
+    /// Where this plan's downloads are saved, picked with the first one. The directory is only
+    /// created when the plan runs, private to the user and refusing to reuse an existing one so
+    /// nothing else can swap an installer before it runs, and removed afterwards.
+    downloads: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Install,
+    Remove,
+}
+
+#[derive(Debug)]
+pub enum InstallStep {
+    /// Downloads `url`, or its mirror from the config, to `dest`, a file in the plan's own
+    /// temporary directory, checking its SHA-256 if one is given.
+    Download {
+        url: String,
+        dest: PathBuf,
+        sha256: Option<String>,
+    },
+    /// Unpacks a tarball or zip into `dest`, dropping the archive's top-level directory.
+    /// Anything already at `dest` is replaced.
+    Extract { archive: PathBuf, dest: PathBuf },
+    /// Writes a small script at `path` that runs `target` with the script's arguments.
+    Shim { path: PathBuf, target: PathBuf },
+    /// Deletes a file or directory.
+    Delete { path: PathBuf },
+    /// Runs a command. Privileged commands install system-wide: package managers that need
+    /// root, and installers that ask for administrator rights.
+    Run {
+        command: Vec<String>,
+        privileged: bool,
+    },
+}
+
+impl Action {
+    pub fn id(self) -> &'static str {
+        match self {
+            Action::Install => "install",
+            Action::Remove => "remove",
+        }
+    }
+}
+
+impl InstallPlan {
+    /// Starts an empty plan installing the tool `tool_id`, described as `what`.
+    pub fn new(tool_id: &str, what: impl Into<String>) -> InstallPlan {
+        InstallPlan {
+            action: Action::Install,
+            what: what.into(),
+            tools: vec![(tool_id.to_string(), Vec::new())],
+            manager: None,
+            method: "installer".to_string(),
+            version: None,
+            steps: Vec::new(),
+            downloads: None,
+        }
+    }
+
+    /// Plans installing `tools` with the package manager detected for this machine, through
+    /// `sudo` when the manager needs root and projector isn't running as root. Packages that
+    /// are already installed are left out. Fails if there's no supported package manager, it
+    /// doesn't carry one of the tools, or it needs root and there's no way to get it.
+    pub fn packages(tools: &[Tool]) -> color_eyre::Result<InstallPlan> {
+        let what = tools
+            .iter()
+            .map(|tool| tool.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let manager = PackageManager::detect().ok_or_else(|| {
+            eyre!(
+                "No supported package manager found. Install {} yourself, then run projector again.",
+                what
+            )
+        })?;
+        let mut planned = Vec::new();
+        for &tool in tools {
+            let provided = manager.packages(tool).ok_or_else(|| {
+                eyre!(
+                    "{} has no package for {}. Install it yourself, then run projector again.",
+                    manager,
+                    tool.name()
+                )
+            })?;
+            let missing: Vec<String> = provided
+                .iter()
+                .filter(|package| !manager.is_installed(package))
+                .map(|package| package.to_string())
+                .collect();
+            planned.push((tool.id().to_string(), missing));
+        }
+        let packages: Vec<&str> = planned
+            .iter()
+            .flat_map(|(_, packages)| packages.iter().map(String::as_str))
+            .collect();
+        if packages.is_empty() {
+            bail!(
+                "The {} packages for {} are already installed, but projector can't find them. Check your PATH.",
+                manager,
+                what
+            );
+        }
+
+        let steps = privileged_commands(
+            manager,
+            manager.install_commands(&packages),
+            Privileges::current(),
+        )?;
+        Ok(InstallPlan {
+            action: Action::Install,
+            what,
+            tools: planned,
+            manager: Some(manager),
+            method: manager.id().to_string(),
+            version: None,
+            steps,
+            downloads: None,
+        })
+    }
+
+    /// Plans removing `packages`, installed earlier for `tool_id`, with `manager`.
+    pub fn removal(
+        tool_id: &str,
+        manager: PackageManager,
+        packages: &[&str],
+    ) -> color_eyre::Result<InstallPlan> {
+        Ok(InstallPlan {
+            action: Action::Remove,
+            what: tool_id.to_string(),
+            tools: vec![(
+                tool_id.to_string(),
+                packages.iter().map(|package| package.to_string()).collect(),
+            )],
+            manager: Some(manager),
+            method: manager.id().to_string(),
+            version: None,
+            steps: privileged_commands(
+                manager,
+                manager.remove_commands(packages),
+                Privileges::current(),
+            )?,
+            downloads: None,
+        })
+    }
+
+    /// Adds a step downloading `url` as `file_name`, returning where the file will be.
+    pub fn download(&mut self, url: &str, file_name: &str) -> color_eyre::Result<PathBuf> {
+        self.download_verified(url, file_name, None)
+    }
+
+    /// Like [`InstallPlan::download`], failing the step unless the file's SHA-256 is `sha256`.
+    pub fn download_verified(
+        &mut self,
+        url: &str,
+        file_name: &str,
+        sha256: Option<&str>,
+    ) -> color_eyre::Result<PathBuf> {
+        let downloads = self.downloads.get_or_insert_with(|| {
+            let random = RandomState::new().hash_one((process::id(), SystemTime::now()));
+            env::temp_dir().join(format!("projector-install-{:016x}", random))
+        });
+        let dest = downloads.join(file_name);
+        self.steps.push(InstallStep::Download {
+            url: url.to_string(),
+            dest: dest.clone(),
+            sha256: sha256.map(str::to_lowercase),
+        });
+        Ok(dest)
+    }
+
+    /// Adds a step running `command`.
+    pub fn run(&mut self, command: &[&str], privileged: bool) -> &mut InstallPlan {
+        self.steps.push(InstallStep::Run {
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+            privileged,
+        });
+        self
+    }
+
+    /// Whether any step installs system-wide.
+    pub fn privileged(&self) -> bool {
+        self.steps.iter().any(|step| {
+            matches!(
+                step,
+                InstallStep::Run {
+                    privileged: true,
+                    ..
+                }
+            )
+        })
+    }
+
+    /// The steps as one line, e.g. for the install history.
+    pub fn command_line(&self) -> String {
+        self.steps
+            .iter()
+            .map(|step| step.to_string())
+            .collect::<Vec<_>>()
+            .join(" && ")
+    }
+
+    pub fn print(&self) {
+        println!("Plan to {} {}:", self.action.id(), self.what);
+        if let Some(manager) = self.manager {
+            match OsRelease::load() {
+                Some(release) => println!(
+                    "  Package manager: {} on {}",
+                    manager,
+                    release.display_name()
+                ),
+                None => println!("  Package manager: {}", manager),
+            }
+            let packages: Vec<&str> = self
+                .tools
+                .iter()
+                .flat_map(|(_, packages)| packages.iter().map(String::as_str))
+                .collect();
+            println!("  Packages: {}", packages.join(" "));
+        }
+        let urls: Vec<&str> = self
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                InstallStep::Download { url, .. } => Some(url.as_str()),
+                _ => None,
+            })
+            .collect();
+        if !urls.is_empty() {
+            println!("  Downloads: {}", urls.join(", "));
+        }
+        println!(
+            "  Needs root or administrator rights: {}",
+            if self.privileged() { "yes" } else { "no" }
+        );
+        for (i, step) in self.steps.iter().enumerate() {
+            println!("  {}. {}", i + 1, step);
+        }
+    }
+
+    /// Runs the steps in order, stopping at the first that fails. Unfinished downloads are kept
+    /// in the cache, so that running the plan again resumes them.
+    pub fn execute(&self) -> color_eyre::Result<()> {
+        self.execute_with(&Downloader::new()?)
+    }
+
+    /// [`InstallPlan::execute`], fetching the downloads with `downloader`.
+    pub fn execute_with(&self, downloader: &Downloader) -> color_eyre::Result<()> {
+        if let Some(downloads) = &self.downloads {
+            create_private_dir(downloads)
+                .map_err(|e| eyre!("couldn't create {}: {}", downloads.display(), e))?;
+        }
+        let result = self
+            .steps
+            .iter()
+            .try_for_each(|step| run_step(step, downloader));
+        if let Some(downloads) = &self.downloads {
+            if let Err(e) = fs::remove_dir_all(downloads) {
+                eprintln!("Warning: Failed to remove {}: {}", downloads.display(), e);
+            }
+        }
+        result
+    }
+}
+
+/// Creates `dir` readable by the user only. Fails if it exists already.
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new().mode(0o700).create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir(dir)
+}
+
+/// Whether projector runs as root, and whether it can become root through `sudo`.
+#[derive(Debug, Clone, Copy)]
+struct Privileges {
+    root: bool,
+    sudo: bool,
+}
+
+impl Privileges {
+    fn current() -> Privileges {
+        Privileges {
+            root: is_root(),
+            sudo: is_on_path("sudo"),
+        }
+    }
+}
+
+/// Turns package manager commands into steps, going through `sudo` if the manager needs root
+/// and projector isn't running as root. Fails if it would need `sudo` and there's none.
+fn privileged_commands(
+    manager: PackageManager,
+    commands: Vec<Vec<String>>,
+    privileges: Privileges,
+) -> color_eyre::Result<Vec<InstallStep>> {
+    let privileged = manager.needs_root();
+    let sudo = privileged && !privileges.root;
+    if sudo && !privileges.sudo {
+        bail!(
+            "{} needs root, but projector isn't running as root and sudo isn't installed. Run projector as root, or run `{}` as root yourself.",
+            manager,
+            commands
+                .iter()
+                .map(|command| command.join(" "))
+                .collect::<Vec<_>>()
+                .join(" && ")
+        );
+    }
+    Ok(commands
+        .into_iter()
+        .map(|mut command| {
+            if sudo {
+                command.insert(0, "sudo".to_string());
+            }
+            InstallStep::Run {
+                command,
+                privileged,
+            }
+        })
+        .collect())
+}
+
+fn run_step(step: &InstallStep, downloader: &Downloader) -> color_eyre::Result<()> {
+    println!("{}", step);
+    match step {
+        InstallStep::Download { url, dest, sha256 } => {
+            downloader.file(url, dest, sha256.as_deref())
+        }
+        InstallStep::Extract { archive, dest } => {
+            // Unpack next to the destination first, so a failure never leaves half a toolchain.
+            // The suffix is appended, since versions like `1.22.1` look like they have an
+            // extension already.
+            let mut partial = dest.as_os_str().to_os_string();
+            partial.push(".partial");
+            let partial = PathBuf::from(partial);
+            if partial.exists() {
+                fs::remove_dir_all(&partial)?;
+            }
+            fs::create_dir_all(&partial)?;
+            let archive_arg = archive.to_string_lossy();
+            let partial_arg = partial.to_string_lossy();
+            if let Err(e) = run(&[
+                "tar",
+                "-xf",
+                &archive_arg,
+                "-C",
+                &partial_arg,
+                "--strip-components=1",
+            ]) {
+                fs::remove_dir_all(&partial)?;
+                return Err(e);
+            }
+            if dest.exists() {
+                fs::remove_dir_all(dest)?;
+            }
+            fs::rename(&partial, dest)?;
+            Ok(())
+        }
+        InstallStep::Shim { path, target } => write_shim(path, target),
+        InstallStep::Delete { path } => {
+            if path.is_dir() {
+                fs::remove_dir_all(path)?;
+            } else if path.exists() {
+                fs::remove_file(path)?;
+            }
+            Ok(())
+        }
+        InstallStep::Run { command, .. } => {
+            let args: Vec<&str> = command.iter().map(String::as_str).collect();
+            run(&args)
+        }
+    }
+}
+
+fn run(command: &[&str]) -> color_eyre::Result<()> {
+    let status = Command::new(command[0])
+        .args(&command[1..])
+        .status()
+        .map_err(|e| eyre!("couldn't start {}: {}", command[0], e))?;
+    if !status.success() {
+        bail!(
+            "`{}` failed with exit code: {:?}",
+            command.join(" "),
+            status.code()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_shim(path: &Path, target: &Path) -> color_eyre::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(
+        path,
+        format!("#!/bin/sh\nexec \"{}\" \"$@\"\n", target.display()),
+    )?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_shim(path: &Path, target: &Path) -> color_eyre::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(
+        path.with_extension("cmd"),
+        format!("@\"{}\" %*\r\n", target.display()),
+    )?;
+    Ok(())
+}
+
+impl fmt::Display for InstallStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InstallStep::Download {
+                url,
+                dest,
+                sha256: Some(sha256),
+            } => write!(
+                f,
+                "Download {} to {} and check its SHA-256 is {}",
+                url,
+                dest.display(),
+                sha256
+            ),
+            InstallStep::Download { url, dest, .. } => {
+                write!(f, "Download {} to {}", url, dest.display())
+            }
+            InstallStep::Extract { archive, dest } => {
+                write!(f, "Unpack {} into {}", archive.display(), dest.display())
+            }
+            InstallStep::Shim { path, target } => {
+                write!(f, "Write {} running {}", path.display(), target.display())
+            }
+            InstallStep::Delete { path } => write!(f, "Delete {}", path.display()),
+            InstallStep::Run { command, .. } => write!(f, "Run `{}`", command.join(" ")),
+        }
+    }
+}
+
+fn is_root() -> bool {
+    Command::new("id")
+        .arg("-u")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "0")
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::install::test_server::{Route, Server};
+
+    fn commands(steps: &[InstallStep]) -> Vec<(String, bool)> {
+        steps
+            .iter()
+            .map(|step| match step {
+                InstallStep::Run {
+                    command,
+                    privileged,
+                } => (command.join(" "), *privileged),
+                step => panic!("unexpected step {}", step),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn root_runs_package_managers_directly() {
+        let privileges = Privileges {
+            root: true,
+            sudo: false,
+        };
+        let steps = privileged_commands(
+            PackageManager::Dnf,
+            PackageManager::Dnf.install_commands(&["gcc"]),
+            privileges,
+        )
+        .unwrap();
+        assert_eq!(commands(&steps), [("dnf install -y gcc".to_string(), true)]);
+    }
+
+    #[test]
+    fn other_users_go_through_sudo() {
+        let privileges = Privileges {
+            root: false,
+            sudo: true,
+        };
+        let steps = privileged_commands(
+            PackageManager::Apt,
+            PackageManager::Apt.install_commands(&["gcc", "make"]),
+            privileges,
+        )
+        .unwrap();
+        assert_eq!(
+            commands(&steps),
+            [
+                ("sudo apt-get update".to_string(), true),
+                ("sudo apt-get install -y gcc make".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn root_is_required_without_sudo() {
+        let privileges = Privileges {
+            root: false,
+            sudo: false,
+        };
+        let error = privileged_commands(
+            PackageManager::Apk,
+            PackageManager::Apk.remove_commands(&["gcc"]),
+            privileges,
+        )
+        .unwrap_err();
+        assert!(
+            error.to_string().contains("sudo isn't installed"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn user_package_managers_never_need_root() {
+        for sudo in [false, true] {
+            let privileges = Privileges { root: false, sudo };
+            let steps = privileged_commands(
+                PackageManager::Brew,
+                PackageManager::Brew.install_commands(&["gcc"]),
+                privileges,
+            )
+            .unwrap();
+            assert_eq!(commands(&steps), [("brew install gcc".to_string(), false)]);
+        }
+    }
+
+    #[test]
+    fn privileged_plans_say_so() {
+        let mut plan = InstallPlan::new("gcc", "GCC");
+        plan.run(&["true"], false);
+        assert!(!plan.privileged());
+        plan.run(&["true"], true);
+        assert!(plan.privileged());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn downloads_directory_is_private_and_only_exists_while_the_plan_runs() {
+        let temp = tempfile::tempdir().unwrap();
+        let server = Server::start(vec![Route::new("/installer", "#!/bin/sh\n")]);
+        let mut plan = InstallPlan::new("tool", "Tool");
+        let installer = plan
+            .download(&server.url("/installer"), "installer")
+            .unwrap();
+        let downloads = installer.parent().unwrap().to_path_buf();
+        assert!(!downloads.exists());
+        assert_ne!(
+            InstallPlan::new("tool", "Tool")
+                .download("https://example.com/installer", "installer")
+                .unwrap(),
+            installer
+        );
+
+        let listing = temp.path().join("listing");
+        plan.run(
+            &[
+                "sh",
+                "-c",
+                &format!(
+                    "ls -ld '{}' > '{}' && test -f '{}'",
+                    downloads.display(),
+                    listing.display(),
+                    installer.display()
+                ),
+            ],
+            false,
+        );
+        plan.execute_with(&Downloader::for_tests(temp.path().join("cache")))
+            .unwrap();
+        assert!(fs::read_to_string(&listing)
+            .unwrap()
+            .starts_with("drwx------"));
+        assert!(!downloads.exists());
+    }
+
+    #[test]
+    fn existing_downloads_directory_is_not_used() {
+        let temp = tempfile::tempdir().unwrap();
+        let server = Server::start(vec![Route::new("/installer", "#!/bin/sh\n")]);
+        let mut plan = InstallPlan::new("tool", "Tool");
+        let installer = plan
+            .download(&server.url("/installer"), "installer")
+            .unwrap();
+        let downloads = installer.parent().unwrap().to_path_buf();
+        fs::create_dir(&downloads).unwrap();
+        fs::write(&installer, "swapped").unwrap();
+
+        let error = plan
+            .execute_with(&Downloader::for_tests(temp.path().join("cache")))
+            .unwrap_err();
+        assert!(
+            error.to_string().starts_with("couldn't create"),
+            "{}",
+            error
+        );
+        assert_eq!(fs::read_to_string(&installer).unwrap(), "swapped");
+        fs::remove_dir_all(downloads).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+//! A local HTTP server standing in for download sites in tests.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A file the server answers a request target with.
+pub struct Route {
+    pub target: String,
+    pub body: Vec<u8>,
+    /// Whether `Range` requests get a `206` with the rest of the body, or a `416` if they start
+    /// past its end. Without, the whole body is sent again.
+    pub ranges: bool,
+}
+
+impl Route {
+    /// Serves `body` at `target`, e.g. `/index.json`. `{base}` in a UTF-8 body is replaced by
+    /// the server's URL.
+    pub fn new(target: &str, body: impl Into<Vec<u8>>) -> Route {
+        Route {
+            target: target.to_string(),
+            body: body.into(),
+            ranges: true,
+        }
+    }
+
+    pub fn without_ranges(mut self) -> Route {
+        self.ranges = false;
+        self
+    }
+}
+
+/// Runs until the test ends. Unknown targets get a `404`.
+pub struct Server {
+    pub base: String,
+    ranges: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl Server {
+    pub fn start(routes: Vec<Route>) -> Server {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Vec<Route> = routes
+            .into_iter()
+            .map(|mut route| {
+                if let Ok(body) = String::from_utf8(route.body.clone()) {
+                    route.body = body.replace("{base}", &base).into_bytes();
+                }
+                route
+            })
+            .collect();
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&ranges);
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut lines = BufReader::new(&stream).lines().map_while(Result::ok);
+                let request = lines.next().unwrap_or_default();
+                let mut range = None;
+                // Requests have no body, so the headers end the request.
+                for line in lines.by_ref() {
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("range") {
+                            range = Some(value.trim().to_string());
+                        }
+                    }
+                }
+                seen.lock().unwrap().push(range.clone());
+                let target = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, headers, body) = match routes.iter().find(|r| r.target == target) {
+                    None => ("404 Not Found", String::new(), &[][..]),
+                    Some(route) => respond(route, range.as_deref()),
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+                    status,
+                    body.len(),
+                    headers
+                );
+                let _ = stream.write_all(body);
+            }
+        });
+        Server { base, ranges }
+    }
+
+    pub fn url(&self, target: &str) -> String {
+        format!("{}{}", self.base, target)
+    }
+
+    /// The `Range` header of each request so far, in order.
+    pub fn ranges(&self) -> Vec<Option<String>> {
+        self.ranges.lock().unwrap().clone()
+    }
+}
+
+fn respond<'a>(route: &'a Route, range: Option<&str>) -> (&'static str, String, &'a [u8]) {
+    let start = range
+        .filter(|_| route.ranges)
+        .and_then(|range| range.strip_prefix("bytes="))
+        .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok());
+    let len = route.body.len();
+    match start {
+        None => ("200 OK", String::new(), &route.body),
+        Some(start) if start >= len => (
+            "416 Range Not Satisfiable",
+            format!("Content-Range: bytes */{}\r\n", len),
+            &[],
+        ),
+        Some(start) => (
+            "206 Partial Content",
+            format!("Content-Range: bytes {}-{}/{}\r\n", start, len - 1, len),
+            &route.body[start..],
+        ),
+    }
+}
@@ -0,0 +1,779 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, eyre};
+use directories::ProjectDirs;
+use serde_json::Value;
+
+use crate::install::download::Downloader;
+use crate::install::plan::{Action, InstallPlan, InstallStep};
+use crate::install::Tool;
+use crate::probe;
+
+/// Toolchains projector can install from their official builds into its own prefix, without
+/// root. See [`resolve`].
+pub const TOOLCHAINS: [&str; 5] = ["go", "node", "python", "zig", "jdk"];
+
+/// A toolchain build picked for this machine, with the checksum its publisher gives for it.
+pub struct Release {
+    /// One of [`TOOLCHAINS`].
+    pub name: &'static str,
+    pub version: String,
+    pub url: String,
+    pub file_name: String,
+    pub sha256: String,
+}
+
+/// The toolchain providing `tool`, if projector can install it without a package manager.
+pub fn for_tool(tool: Tool) -> Option<&'static str> {
+    match tool {
+        Tool::Go => Some("go"),
+        Tool::Node => Some("node"),
+        Tool::Python3 => Some("python"),
+        _ => None,
+    }
+}
+
+/// Projector's prefix for toolchains, next to its database: `toolchains/<name>/<version>` holds
+/// the unpacked builds and `bin` the shims that run them.
+pub fn prefix() -> color_eyre::Result<PathBuf> {
+    ProjectDirs::from("rs", "", "projector")
+        .map(|dirs| dirs.data_dir().to_path_buf())
+        .ok_or_else(|| eyre!("Couldn't find a data directory for projector"))
+}
+
+/// The directory of shims, which goes on the PATH.
+pub fn bin_dir() -> color_eyre::Result<PathBuf> {
+    Ok(prefix()?.join("bin"))
+}
+
+fn install_dir(name: &str, version: &str) -> color_eyre::Result<PathBuf> {
+    Ok(prefix()?.join("toolchains").join(name).join(version))
+}
+
+/// Looks up `version` of the toolchain `name` for this OS and architecture, or the latest stable
+/// one if no version is given (the latest LTS for Node, 21 for the JDK). Indexes are fetched
+/// from the official download sites, or their mirrors from `download_mirrors` in the config.
+pub fn resolve(name: &str, version: Option<&str>) -> color_eyre::Result<Release> {
+    // Unknown names have no site, and are reported by `resolve_from`.
+    let downloader = Downloader::new()?;
+    resolve_from(
+        &downloader,
+        name,
+        official_site(name).unwrap_or_default(),
+        version,
+    )
+}
+
+/// Where the toolchain `name` publishes its download index.
+fn official_site(name: &str) -> Option<&'static str> {
+    match name {
+        "go" => Some("https://go.dev/dl"),
+        "node" => Some("https://nodejs.org/dist"),
+        "python" => Some(
+            "https://raw.githubusercontent.com/astral-sh/python-build-standalone/latest-release",
+        ),
+        "zig" => Some("https://ziglang.org/download"),
+        "jdk" => Some("https://api.adoptium.net"),
+        _ => None,
+    }
+}
+
+/// Like [`resolve`], reading the index of `name` from `base` instead of its official site, with
+/// `downloader`.
+fn resolve_from(
+    downloader: &Downloader,
+    name: &str,
+    base: &str,
+    version: Option<&str>,
+) -> color_eyre::Result<Release> {
+    let base = base.trim_end_matches('/');
+    match name {
+        "go" => resolve_go(downloader, base, version),
+        "node" => resolve_node(downloader, base, version),
+        "python" => resolve_python(downloader, base, version),
+        "zig" => resolve_zig(downloader, base, version),
+        "jdk" => resolve_jdk(downloader, base, version),
+        _ => bail!(
+            "Unknown toolchain '{}'. Pick one of: {}",
+            name,
+            TOOLCHAINS.join(", ")
+        ),
+    }
+}
+
+/// Plans downloading `release`, checking its checksum, unpacking it into projector's prefix and
+/// pointing the shims at it.
+pub fn plan(release: &Release) -> color_eyre::Result<InstallPlan> {
+    plan_in(release, &prefix()?)
+}
+
+/// [`plan`], installing into `prefix` instead of projector's own.
+fn plan_in(release: &Release, prefix: &Path) -> color_eyre::Result<InstallPlan> {
+    let dest = prefix
+        .join("toolchains")
+        .join(release.name)
+        .join(&release.version);
+    let bin = prefix.join("bin");
+    let mut plan = InstallPlan::new(
+        release.name,
+        format!("{} {}", display_name(release.name), release.version),
+    );
+    plan.method = "local".to_string();
+    plan.version = Some(release.version.clone());
+    plan.tools[0].1 = vec![format!("{}-{}", release.name, release.version)];
+    let archive =
+        plan.download_verified(&release.url, &release.file_name, Some(&release.sha256))?;
+    plan.steps.push(InstallStep::Extract {
+        archive,
+        dest: dest.clone(),
+    });
+    for (shim, target) in shims(release.name) {
+        plan.steps.push(InstallStep::Shim {
+            path: bin.join(shim),
+            target: dest.join(target),
+        });
+    }
+    Ok(plan)
+}
+
+/// Plans removing `version` of the toolchain `name`, along with the shims still pointing at it.
+pub fn removal(name: &str, version: &str) -> color_eyre::Result<InstallPlan> {
+    let dir = install_dir(name, version)?;
+    if !dir.exists() {
+        bail!("{} {} isn't installed in {}", name, version, dir.display());
+    }
+    let mut plan = InstallPlan::new(name, format!("{} {}", display_name(name), version));
+    plan.action = Action::Remove;
+    plan.method = "local".to_string();
+    plan.tools[0].1 = vec![format!("{}-{}", name, version)];
+    plan.steps.push(InstallStep::Delete { path: dir.clone() });
+    let bin = bin_dir()?;
+    for (shim, _) in shims(name) {
+        let path = bin.join(shim);
+        if shim_target(&path).is_some_and(|target| target.starts_with(&dir)) {
+            plan.steps.push(InstallStep::Delete { path });
+        }
+    }
+    Ok(plan)
+}
+
+/// The shell lines putting projector's toolchains first on the PATH, plus `JAVA_HOME` when a
+/// JDK is installed. PowerShell syntax on Windows, POSIX shell elsewhere.
+pub fn env_lines() -> color_eyre::Result<Vec<String>> {
+    let bin = bin_dir()?;
+    let java_home = shim_target(&bin.join(shim_file("java")))
+        .and_then(|java| java.parent()?.parent().map(Path::to_path_buf));
+    let mut lines = Vec::new();
+    if cfg!(target_os = "windows") {
+        lines.push(format!("$env:PATH = \"{};\" + $env:PATH", bin.display()));
+        if let Some(home) = java_home {
+            lines.push(format!("$env:JAVA_HOME = \"{}\"", home.display()));
+        }
+    } else {
+        lines.push(format!("export PATH=\"{}:$PATH\"", bin.display()));
+        if let Some(home) = java_home {
+            lines.push(format!("export JAVA_HOME=\"{}\"", home.display()));
+        }
+    }
+    Ok(lines)
+}
+
+/// Puts the shims first on this process's PATH, so a toolchain installed just now can be used
+/// without restarting the shell.
+pub fn activate() -> color_eyre::Result<()> {
+    let bin = bin_dir()?;
+    let path = env::var_os("PATH").unwrap_or_default();
+    let paths = std::iter::once(bin).chain(env::split_paths(&path));
+    env::set_var("PATH", env::join_paths(paths)?);
+    probe::forget_all();
+    Ok(())
+}
+
+fn display_name(name: &str) -> &str {
+    match name {
+        "go" => "Go",
+        "node" => "Node.js",
+        "python" => "Python",
+        "zig" => "Zig",
+        "jdk" => "JDK",
+        name => name,
+    }
+}
+
+/// Shim names with the binaries they run, relative to the unpacked toolchain.
+fn shims(name: &str) -> Vec<(String, String)> {
+    let windows = cfg!(target_os = "windows");
+    let pairs: Vec<(&str, String)> = match name {
+        "go" => ["go", "gofmt"]
+            .iter()
+            .map(|bin| (*bin, format!("bin/{}", exe(bin))))
+            .collect(),
+        "node" if windows => vec![
+            ("node", "node.exe".to_string()),
+            ("npm", "npm.cmd".to_string()),
+            ("npx", "npx.cmd".to_string()),
+        ],
+        "node" => ["node", "npm", "npx"]
+            .iter()
+            .map(|bin| (*bin, format!("bin/{}", bin)))
+            .collect(),
+        "python" if windows => vec![
+            ("python", "python.exe".to_string()),
+            ("python3", "python.exe".to_string()),
+            ("pip", "Scripts/pip.exe".to_string()),
+            ("pip3", "Scripts/pip.exe".to_string()),
+        ],
+        "python" => vec![
+            ("python", "bin/python3".to_string()),
+            ("python3", "bin/python3".to_string()),
+            ("pip", "bin/pip3".to_string()),
+            ("pip3", "bin/pip3".to_string()),
+        ],
+        "zig" => vec![("zig", exe("zig"))],
+        "jdk" => {
+            // macOS builds are app bundles.
+            let bin = if cfg!(target_os = "macos") {
+                "Contents/Home/bin"
+            } else {
+                "bin"
+            };
+            ["java", "javac", "jar"]
+                .iter()
+                .map(|tool| (*tool, format!("{}/{}", bin, exe(tool))))
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    pairs
+        .into_iter()
+        .map(|(shim, target)| (shim_file(shim), target))
+        .collect()
+}
+
+fn exe(name: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn shim_file(name: &str) -> String {
+    if cfg!(target_os = "windows") {
+        format!("{}.cmd", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// The binary a shim runs: the first quoted path in it.
+fn shim_target(shim: &Path) -> Option<PathBuf> {
+    let contents = fs::read_to_string(shim).ok()?;
+    let start = contents.find('"')? + 1;
+    let end = start + contents[start..].find('"')?;
+    Some(PathBuf::from(&contents[start..end]))
+}
+
+fn fetch_json(downloader: &Downloader, url: &str) -> color_eyre::Result<Value> {
+    serde_json::from_str(&downloader.text(url)?)
+        .map_err(|e| eyre!("{} isn't valid JSON: {}", url, e))
+}
+
+fn no_build(name: &str, version: Option<&str>) -> color_eyre::Report {
+    eyre!(
+        "No {} build{} found for {} on {}",
+        display_name(name),
+        version.map(|v| format!(" of {}", v)).unwrap_or_default(),
+        env::consts::OS,
+        env::consts::ARCH
+    )
+}
+
+fn resolve_go(
+    downloader: &Downloader,
+    base: &str,
+    version: Option<&str>,
+) -> color_eyre::Result<Release> {
+    let (os, arch) = match (env::consts::OS, env::consts::ARCH) {
+        (os @ ("linux" | "windows"), "x86_64") => (os, "amd64"),
+        (os @ ("linux" | "windows"), "aarch64") => (os, "arm64"),
+        ("macos", "x86_64") => ("darwin", "amd64"),
+        ("macos", "aarch64") => ("darwin", "arm64"),
+        _ => return Err(no_build("go", version)),
+    };
+    let index = fetch_json(downloader, &format!("{}/?mode=json&include=all", base))?;
+    let wanted = version.map(|v| format!("go{}", v.trim_start_matches("go")));
+    let release = index
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|release| match &wanted {
+            Some(wanted) => release["version"].as_str() == Some(wanted.as_str()),
+            None => release["stable"].as_bool() == Some(true),
+        })
+        .ok_or_else(|| no_build("go", version))?;
+    let file = release["files"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|file| {
+            file["os"].as_str() == Some(os)
+                && file["arch"].as_str() == Some(arch)
+                && file["kind"].as_str() == Some("archive")
+        })
+        .ok_or_else(|| no_build("go", version))?;
+    let file_name = string(file, "filename")?;
+    Ok(Release {
+        name: "go",
+        version: string(release, "version")?
+            .trim_start_matches("go")
+            .to_string(),
+        url: format!("{}/{}", base, file_name),
+        sha256: string(file, "sha256")?,
+        file_name,
+    })
+}
+
+fn resolve_node(
+    downloader: &Downloader,
+    base: &str,
+    version: Option<&str>,
+) -> color_eyre::Result<Release> {
+    let os = match env::consts::OS {
+        "macos" => "darwin",
+        "windows" => "win",
+        os => os,
+    };
+    let arch = match env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        _ => return Err(no_build("node", version)),
+    };
+    let index = fetch_json(downloader, &format!("{}/index.json", base))?;
+    let wanted = version.map(|v| format!("v{}", v.trim_start_matches('v')));
+    let release = index
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|release| match &wanted {
+            Some(wanted) => release["version"].as_str() == Some(wanted.as_str()),
+            // `lts` is false, or the release line's codename.
+            None => release["lts"].is_string(),
+        })
+        .ok_or_else(|| no_build("node", version))?;
+    let tag = string(release, "version")?;
+    let extension = if os == "win" { "zip" } else { "tar.gz" };
+    let file_name = format!("node-{}-{}-{}.{}", tag, os, arch, extension);
+    let sha256 =
+        downloader.published_sha256(&format!("{}/{}/SHASUMS256.txt", base, tag), &file_name)?;
+    Ok(Release {
+        name: "node",
+        version: tag.trim_start_matches('v').to_string(),
+        url: format!("{}/{}/{}", base, tag, file_name),
+        file_name,
+        sha256,
+    })
+}
+
+/// Python comes from python-build-standalone, whose `install_only` builds are relocatable.
+fn resolve_python(
+    downloader: &Downloader,
+    base: &str,
+    version: Option<&str>,
+) -> color_eyre::Result<Release> {
+    let triple = match (env::consts::OS, env::consts::ARCH) {
+        ("linux", arch @ ("x86_64" | "aarch64")) => format!("{}-unknown-linux-gnu", arch),
+        ("macos", arch @ ("x86_64" | "aarch64")) => format!("{}-apple-darwin", arch),
+        ("windows", "x86_64") => "x86_64-pc-windows-msvc".to_string(),
+        _ => return Err(no_build("python", version)),
+    };
+    let latest = fetch_json(downloader, &format!("{}/latest-release.json", base))?;
+    let prefix = string(&latest, "asset_url_prefix")?;
+    let tag = string(&latest, "tag")?;
+    let sums = downloader.text(&format!("{}/SHA256SUMS", prefix))?;
+    let suffix = format!("+{}-{}-install_only.tar.gz", tag, triple);
+    // The newest matching build, comparing versions numerically.
+    let (python_version, file_name, sha256) = sums
+        .lines()
+        .filter_map(|line| {
+            let (hash, file_name) = line.split_once(char::is_whitespace)?;
+            let file_name = file_name.trim();
+            let python_version = file_name.strip_prefix("cpython-")?.strip_suffix(&suffix)?;
+            let matches = match version {
+                Some(wanted) => {
+                    python_version == wanted || python_version.starts_with(&format!("{}.", wanted))
+                }
+                None => python_version
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == '.'),
+            };
+            matches.then(|| {
+                (
+                    python_version.to_string(),
+                    file_name.to_string(),
+                    hash.to_lowercase(),
+                )
+            })
+        })
+        .max_by_key(|(python_version, _, _)| numeric_version(python_version))
+        .ok_or_else(|| no_build("python", version))?;
+    Ok(Release {
+        name: "python",
+        version: python_version,
+        url: format!("{}/{}", prefix, file_name.replace('+', "%2B")),
+        file_name,
+        sha256,
+    })
+}
+
+fn resolve_zig(
+    downloader: &Downloader,
+    base: &str,
+    version: Option<&str>,
+) -> color_eyre::Result<Release> {
+    let os = match env::consts::OS {
+        "macos" => "macos",
+        "windows" => "windows",
+        "linux" => "linux",
+        _ => return Err(no_build("zig", version)),
+    };
+    let target = format!("{}-{}", env::consts::ARCH, os);
+    let index = fetch_json(downloader, &format!("{}/index.json", base))?;
+    let releases = index
+        .as_object()
+        .ok_or_else(|| eyre!("Unexpected Zig download index"))?;
+    let zig_version = match version {
+        Some(version) => version.to_string(),
+        None => releases
+            .keys()
+            .filter(|key| *key != "master")
+            .max_by_key(|key| numeric_version(key))
+            .cloned()
+            .ok_or_else(|| no_build("zig", version))?,
+    };
+    let build = releases
+        .get(&zig_version)
+        .and_then(|release| release.get(&target))
+        .ok_or_else(|| no_build("zig", version))?;
+    let tarball = string(build, "tarball")?;
+    let file_name = tarball.rsplit('/').next().unwrap_or(&tarball).to_string();
+    Ok(Release {
+        name: "zig",
+        version: zig_version,
+        url: tarball.clone(),
+        file_name,
+        sha256: string(build, "shasum")?,
+    })
+}
+
+/// JDKs are Eclipse Temurin builds from Adoptium. `version` is the feature release, e.g. `21`.
+fn resolve_jdk(
+    downloader: &Downloader,
+    base: &str,
+    version: Option<&str>,
+) -> color_eyre::Result<Release> {
+    let os = match env::consts::OS {
+        "macos" => "mac",
+        os => os,
+    };
+    let arch = match env::consts::ARCH {
+        "x86_64" => "x64",
+        arch => arch,
+    };
+    let feature = version.unwrap_or("21");
+    let url = format!(
+        "{}/v3/assets/latest/{}/hotspot?architecture={}&image_type=jdk&os={}&vendor=eclipse",
+        base, feature, arch, os
+    );
+    let assets = fetch_json(downloader, &url)?;
+    let asset = assets
+        .as_array()
+        .and_then(|assets| assets.first())
+        .ok_or_else(|| no_build("jdk", version))?;
+    let package = &asset["binary"]["package"];
+    Ok(Release {
+        name: "jdk",
+        version: string(&asset["version"], "semver")?,
+        url: string(package, "link")?,
+        file_name: string(package, "name")?,
+        sha256: string(package, "checksum")?,
+    })
+}
+
+fn string(value: &Value, key: &str) -> color_eyre::Result<String> {
+    value[key]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| eyre!("Download index is missing '{}'", key))
+}
+
+/// `1.22.10` as `[1, 22, 10]`, so versions compare numerically. Non-numeric parts count as 0.
+fn numeric_version(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+// The fixtures name the builds for 64-bit x86 Linux, which the lookups pick on that platform.
+// The fixtures name the builds for 64-bit x86 Linux, which the lookups pick on that platform.
+#[cfg(all(test, target_os = "linux", target_arch = "x86_64"))]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+    use crate::install::download;
+    use crate::install::test_server::{Route, Server};
+
+    /// Serves `routes`, request targets with their bodies, returning the server's URL.
+    fn serve(routes: &[(&str, &str)]) -> String {
+        let routes = routes
+            .iter()
+            .map(|(target, body)| Route::new(target, *body))
+            .collect();
+        Server::start(routes).base
+    }
+
+    /// [`resolve_from`] without the user's mirrors or proxy. Looking up a release doesn't
+    /// download anything, so there's no cache.
+    fn lookup(name: &str, base: &str, version: Option<&str>) -> color_eyre::Result<Release> {
+        resolve_from(
+            &Downloader::for_tests(PathBuf::from("unused")),
+            name,
+            base,
+            version,
+        )
+    }
+
+    /// A Go-like `.tar.gz` with everything under a top-level `go` directory.
+    fn go_archive(dir: &Path) -> Vec<u8> {
+        let bin = dir.join("src/go/bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("go"), "#!/bin/sh\necho go version go1.99.0\n").unwrap();
+        fs::write(bin.join("gofmt"), "#!/bin/sh\n").unwrap();
+        let archive = dir.join("go.tar.gz");
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(dir.join("src"))
+            .arg("go")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fs::read(archive).unwrap()
+    }
+
+    fn go_release(server: &Server, sha256: String) -> Release {
+        let file_name = "go1.99.0.linux-amd64.tar.gz".to_string();
+        Release {
+            name: "go",
+            version: "1.99.0".to_string(),
+            url: server.url(&format!("/{}", file_name)),
+            file_name,
+            sha256,
+        }
+    }
+
+    fn part_files(cache: &Path) -> usize {
+        fs::read_dir(cache).map_or(0, |entries| entries.count())
+    }
+
+    #[test]
+    fn plan_downloads_verifies_unpacks_and_writes_shims() {
+        let temp = tempfile::tempdir().unwrap();
+        let archive = go_archive(temp.path());
+        let server = Server::start(vec![Route::new(
+            "/go1.99.0.linux-amd64.tar.gz",
+            archive.clone(),
+        )]);
+        let archive_path = temp.path().join("go.tar.gz");
+        let release = go_release(&server, download::sha256_file(&archive_path).unwrap());
+        let prefix = temp.path().join("prefix");
+        let dest = prefix.join("toolchains/go/1.99.0");
+        // A leftover from an earlier install is replaced.
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(dest.join("stale"), "").unwrap();
+        let cache = temp.path().join("cache");
+
+        plan_in(&release, &prefix)
+            .unwrap()
+            .execute_with(&Downloader::for_tests(cache.clone()))
+            .unwrap();
+
+        assert!(dest.join("bin/go").is_file());
+        assert!(dest.join("bin/gofmt").is_file());
+        assert!(!dest.join("stale").exists());
+        assert!(!prefix.join("toolchains/go/1.99.0.partial").exists());
+        assert_eq!(
+            shim_target(&prefix.join("bin/go")),
+            Some(dest.join("bin/go"))
+        );
+        assert_eq!(
+            shim_target(&prefix.join("bin/gofmt")),
+            Some(dest.join("bin/gofmt"))
+        );
+        assert_eq!(part_files(&cache), 0);
+    }
+
+    #[test]
+    fn checksum_mismatch_installs_nothing() {
+        let temp = tempfile::tempdir().unwrap();
+        let archive = go_archive(temp.path());
+        let server = Server::start(vec![Route::new("/go1.99.0.linux-amd64.tar.gz", archive)]);
+        let release = go_release(&server, "0".repeat(64));
+        let prefix = temp.path().join("prefix");
+        let cache = temp.path().join("cache");
+
+        let error = plan_in(&release, &prefix)
+            .unwrap()
+            .execute_with(&Downloader::for_tests(cache.clone()))
+            .unwrap_err();
+        assert!(error.to_string().starts_with("checksum mismatch for"));
+        assert!(!prefix.join("toolchains").exists());
+        assert!(!prefix.join("bin").exists());
+        assert_eq!(part_files(&cache), 0);
+    }
+
+    #[test]
+    fn go_picks_the_latest_stable_or_the_asked_version() {
+        let base = serve(&[(
+            "/?mode=json&include=all",
+            r#"[
+                {"version": "go1.23rc1", "stable": false, "files": []},
+                {"version": "go1.22.1", "stable": true, "files": [
+                    {"filename": "go1.22.1.darwin-arm64.tar.gz", "os": "darwin", "arch": "arm64", "kind": "archive", "sha256": "mac"},
+                    {"filename": "go1.22.1.linux-amd64.msi", "os": "linux", "arch": "amd64", "kind": "installer", "sha256": "msi"},
+                    {"filename": "go1.22.1.linux-amd64.tar.gz", "os": "linux", "arch": "amd64", "kind": "archive", "sha256": "linux"}
+                ]},
+                {"version": "go1.21.0", "stable": true, "files": [
+                    {"filename": "go1.21.0.linux-amd64.tar.gz", "os": "linux", "arch": "amd64", "kind": "archive", "sha256": "old"}
+                ]}
+            ]"#,
+        )]);
+
+        let latest = lookup("go", &base, None).unwrap();
+        assert_eq!(latest.version, "1.22.1");
+        assert_eq!(latest.file_name, "go1.22.1.linux-amd64.tar.gz");
+        assert_eq!(latest.url, format!("{}/go1.22.1.linux-amd64.tar.gz", base));
+        assert_eq!(latest.sha256, "linux");
+
+        let pinned = lookup("go", &base, Some("go1.21.0")).unwrap();
+        assert_eq!(pinned.version, "1.21.0");
+        assert_eq!(pinned.sha256, "old");
+
+        let missing = lookup("go", &base, Some("1.99")).err().unwrap();
+        assert!(missing.to_string().starts_with("No Go build of 1.99"));
+    }
+
+    #[test]
+    fn node_picks_the_latest_lts_and_its_published_checksum() {
+        let base = serve(&[
+            (
+                "/index.json",
+                r#"[
+                    {"version": "v23.1.0", "lts": false},
+                    {"version": "v22.11.0", "lts": "Jod"},
+                    {"version": "v20.18.0", "lts": "Iron"}
+                ]"#,
+            ),
+            (
+                "/v22.11.0/SHASUMS256.txt",
+                "AAA  node-v22.11.0-darwin-arm64.tar.gz\nBBB  node-v22.11.0-linux-x64.tar.gz\n",
+            ),
+        ]);
+
+        let release = lookup("node", &format!("{}/", base), None).unwrap();
+        assert_eq!(release.version, "22.11.0");
+        assert_eq!(
+            release.url,
+            format!("{}/v22.11.0/node-v22.11.0-linux-x64.tar.gz", base)
+        );
+        assert_eq!(release.sha256, "bbb");
+        // v20 has no checksum manifest on the stand-in.
+        assert!(lookup("node", &base, Some("20.18.0")).is_err());
+    }
+
+    #[test]
+    fn python_picks_the_newest_matching_build() {
+        let base = serve(&[
+            (
+                "/latest-release.json",
+                r#"{"tag": "20241016", "asset_url_prefix": "{base}/download/20241016"}"#,
+            ),
+            (
+                "/download/20241016/SHA256SUMS",
+                "a1 cpython-3.9.20+20241016-x86_64-unknown-linux-gnu-install_only.tar.gz\n\
+                 a2 cpython-3.12.7+20241016-x86_64-unknown-linux-gnu-install_only.tar.gz\n\
+                 a3 cpython-3.13.0+20241016-x86_64-unknown-linux-gnu-install_only.tar.gz\n\
+                 a4 cpython-3.14.0a1+20241016-x86_64-unknown-linux-gnu-install_only.tar.gz\n\
+                 a5 cpython-3.13.0+20241016-aarch64-apple-darwin-install_only.tar.gz\n",
+            ),
+        ]);
+
+        let latest = lookup("python", &base, None).unwrap();
+        assert_eq!(latest.version, "3.13.0");
+        assert_eq!(latest.sha256, "a3");
+        assert_eq!(
+            latest.url,
+            format!(
+                "{}/download/20241016/cpython-3.13.0%2B20241016-x86_64-unknown-linux-gnu-install_only.tar.gz",
+                base
+            )
+        );
+
+        let minor = lookup("python", &base, Some("3.12")).unwrap();
+        assert_eq!(minor.version, "3.12.7");
+        assert!(lookup("python", &base, Some("3.1")).is_err());
+    }
+
+    #[test]
+    fn zig_compares_versions_numerically() {
+        let base = serve(&[(
+            "/index.json",
+            r#"{
+                "master": {"x86_64-linux": {"tarball": "https://example.com/zig-master.tar.xz", "shasum": "m"}},
+                "0.9.1": {"x86_64-linux": {"tarball": "https://example.com/zig-linux-x86_64-0.9.1.tar.xz", "shasum": "old"}},
+                "0.13.0": {"x86_64-linux": {"tarball": "https://example.com/zig-linux-x86_64-0.13.0.tar.xz", "shasum": "new"}}
+            }"#,
+        )]);
+
+        let release = lookup("zig", &base, None).unwrap();
+        assert_eq!(release.version, "0.13.0");
+        assert_eq!(release.file_name, "zig-linux-x86_64-0.13.0.tar.xz");
+        assert_eq!(release.sha256, "new");
+    }
+
+    #[test]
+    fn jdk_asks_for_the_feature_release() {
+        let base = serve(&[(
+            "/v3/assets/latest/17/hotspot?architecture=x64&image_type=jdk&os=linux&vendor=eclipse",
+            r#"[{
+                "version": {"semver": "17.0.13+11"},
+                "binary": {"package": {
+                    "link": "https://example.com/OpenJDK17U-jdk_x64_linux_hotspot_17.0.13_11.tar.gz",
+                    "name": "OpenJDK17U-jdk_x64_linux_hotspot_17.0.13_11.tar.gz",
+                    "checksum": "jdk"
+                }}
+            }]"#,
+        )]);
+
+        let release = lookup("jdk", &base, Some("17")).unwrap();
+        assert_eq!(release.version, "17.0.13+11");
+        assert_eq!(
+            release.file_name,
+            "OpenJDK17U-jdk_x64_linux_hotspot_17.0.13_11.tar.gz"
+        );
+        assert_eq!(release.sha256, "jdk");
+        // 21 is the default, which the stand-in doesn't serve.
+        assert!(lookup("jdk", &base, None).is_err());
+    }
+
+    #[test]
+    fn unknown_toolchains_are_rejected() {
+        let error = resolve("ruby", None).err().unwrap();
+        assert!(error.to_string().starts_with("Unknown toolchain 'ruby'"));
+    }
+}
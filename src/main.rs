@@ -1,12 +1,18 @@
-use clap::{Parser, Subcommand};
-use std::io::{self, Write};
-use std::process::Command;
-use ui::{interactive_setup, not_implemented_warning, settings, yn};
+use clap::{Args, Parser, Subcommand};
+use scaffold::ScaffoldOptions;
+use state::languages::ProgrammingLanguage;
+use std::path::PathBuf;
+use ui::{interactive_setup, not_implemented_warning, prompt, settings};
 
 mod database;
+mod doctor;
+mod install;
+mod pins;
 mod post_setup;
+mod probe;
 mod scaffold;
 mod state;
+mod template;
 mod ui;
 pub mod util;
 
@@ -14,61 +20,289 @@ pub mod util;
 #[command(name = "projector")]
 #[command(about = "Projector CLI")]
 struct Cli {
+    /// Accept the default answer for every question instead of prompting
+    #[arg(long, short = 'y', global = true, visible_alias = "no-input")]
+    yes: bool,
+
+    /// Ask questions as plain lines of text instead of full-screen prompts
+    #[arg(long, global = true)]
+    plain: bool,
+
+    /// Answer questions from FILE, one answer per line, instead of asking
+    #[arg(long, global = true, value_name = "FILE")]
+    answers: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Scaffold a new project
     New {
         lang: Option<String>,
         project_name: Option<String>,
+        /// Render a template from a local directory, git URL or template name. With a single
+        /// positional argument, that argument is the project name.
+        #[arg(long, short = 't', value_name = "PATH|URL|NAME")]
+        template: Option<String>,
+        #[command(flatten)]
+        scaffold: Box<ScaffoldArgs>,
     },
     Settings,
     Gallery,
+    /// List the templates available for scaffolding
+    Templates,
+    /// Inspect and undo the tool installs projector has run
+    Tools {
+        #[command(subcommand)]
+        command: ToolsCommand,
+    },
+    /// Check the tools, config, database, editor and PATH projector relies on
+    Doctor {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print shell lines that put projector's toolchains on the PATH, for
+    /// `eval "$(projector env)"`
+    Env,
     Add,
     Clear,
 }
 
+#[derive(Subcommand)]
+enum ToolsCommand {
+    /// List every install and removal projector has run
+    History,
+    /// Install a toolchain from its official builds for this user only, without root: go, node,
+    /// python, zig or jdk
+    Install {
+        toolchain: String,
+        /// Version to install, e.g. `1.22.1`; the latest stable release by default
+        #[arg(long)]
+        version: Option<String>,
+        /// Print the install plan without running it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Remove a tool projector installed, e.g. `gcc`
+    Remove {
+        tool: String,
+        /// Print the removal plan without running it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Flags that answer scaffolding questions ahead of time.
+#[derive(Args)]
+struct ScaffoldArgs {
+    /// Create a virtual environment and install dev dependencies (Python)
+    #[arg(long, overrides_with = "no_venv")]
+    venv: bool,
+    #[arg(long, overrides_with = "venv")]
+    no_venv: bool,
+
+    /// Pin installed packages in requirements.txt (Python with pip)
+    #[arg(long, overrides_with = "no_requirements")]
+    requirements: bool,
+    #[arg(long, overrides_with = "requirements")]
+    no_requirements: bool,
+
+    /// Initialize a git repository
+    #[arg(long, overrides_with = "no_git")]
+    git: bool,
+    #[arg(long, overrides_with = "git")]
+    no_git: bool,
+
+    /// Write a .gitignore file when git is set up
+    #[arg(long, overrides_with = "no_gitignore")]
+    gitignore: bool,
+    #[arg(long, overrides_with = "gitignore")]
+    no_gitignore: bool,
+
+    /// Package manager: npm, pnpm, yarn or bun (JavaScript), pip, uv, poetry, hatch or pdm (Python)
+    #[arg(long, value_name = "MANAGER")]
+    package_manager: Option<String>,
+
+    /// Use TypeScript (JavaScript)
+    #[arg(long, overrides_with = "no_typescript")]
+    typescript: bool,
+    #[arg(long, overrides_with = "typescript")]
+    no_typescript: bool,
+
+    /// Use ES modules (JavaScript)
+    #[arg(long, overrides_with = "cjs")]
+    esm: bool,
+    /// Use CommonJS modules (JavaScript)
+    #[arg(long, overrides_with = "esm")]
+    cjs: bool,
+
+    /// Build system: maven or gradle (Java, Kotlin), cmake, meson or make (C, C++)
+    #[arg(long, value_name = "NAME")]
+    build_system: Option<String>,
+
+    /// Project layout, e.g. single or cmd (Go)
+    #[arg(long, value_name = "LAYOUT")]
+    layout: Option<String>,
+
+    /// Project kind: bin, lib or workspace (Rust), console, classlib, webapi or xunit (C#),
+    /// executable or library (Swift)
+    #[arg(long, value_name = "KIND")]
+    kind: Option<String>,
+
+    /// Create a library, same as `--kind lib` (Rust)
+    #[arg(long, conflicts_with = "kind")]
+    lib: bool,
+
+    /// Rust edition: 2024, 2021 or 2018
+    #[arg(long, value_name = "EDITION")]
+    edition: Option<String>,
+
+    /// Starter preset: cli or service for binaries, bench for libraries (Rust)
+    #[arg(long, value_name = "PRESET")]
+    preset: Option<String>,
+
+    /// Add a workspace member crate, e.g. `--member core:lib` (Rust)
+    #[arg(long = "member", value_name = "NAME[:bin|lib]")]
+    members: Vec<String>,
+
+    /// Write rust-toolchain.toml, rustfmt.toml and clippy.toml (Rust)
+    #[arg(long, overrides_with = "no_toolchain_files")]
+    toolchain_files: bool,
+    #[arg(long, overrides_with = "toolchain_files")]
+    no_toolchain_files: bool,
+
+    /// Create a solution file with a test project (C#)
+    #[arg(long, overrides_with = "no_solution")]
+    solution: bool,
+    #[arg(long, overrides_with = "solution")]
+    no_solution: bool,
+
+    /// Scaffold a framework, e.g. fastapi or spring-boot, or `none` for a plain project. With a
+    /// single positional argument, that argument is the project name.
+    #[arg(long, value_name = "NAME")]
+    framework: Option<String>,
+
+    /// When the project directory isn't empty: refuse, or merge keeping existing files
+    #[arg(long, value_name = "refuse|merge")]
+    if_exists: Option<String>,
+
+    /// Print what would be created and run, without changing anything
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Set a template variable, e.g. `--var author="Jane Doe"`
+    #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, String)>,
+}
+
+impl ScaffoldArgs {
+    fn into_options(self, assume_yes: bool) -> ScaffoldOptions {
+        ScaffoldOptions {
+            venv: flag(self.venv, self.no_venv),
+            requirements: flag(self.requirements, self.no_requirements),
+            git: flag(self.git, self.no_git),
+            gitignore: flag(self.gitignore, self.no_gitignore),
+            package_manager: self.package_manager,
+            typescript: flag(self.typescript, self.no_typescript),
+            esm: flag(self.esm, self.cjs),
+            build_system: self.build_system,
+            layout: self.layout,
+            kind: if self.lib {
+                Some("lib".to_string())
+            } else {
+                self.kind
+            },
+            solution: flag(self.solution, self.no_solution),
+            edition: self.edition,
+            preset: self.preset,
+            members: self.members,
+            toolchain_files: flag(self.toolchain_files, self.no_toolchain_files),
+            framework: self.framework,
+            if_exists: self.if_exists,
+            variables: self.variables,
+            assume_yes,
+            dry_run: self.dry_run,
+        }
+    }
+}
+
+/// Collapses a `--x`/`--no-x` pair into an answer, or `None` if neither was given.
+fn flag(yes: bool, no: bool) -> Option<bool> {
+    match (yes, no) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_variable(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected NAME=VALUE, got '{}'", arg))
+}
+
 fn main() -> color_eyre::Result<()> {
     let cli = Cli::parse();
+    let mut prompter = prompt::select(cli.plain, cli.answers.as_deref())?;
 
-    match &cli.command {
-        Commands::New { lang, project_name } => {
-            if lang.is_none() {
-                interactive_setup::show()?;
-                return Ok(());
+    match cli.command {
+        Commands::New {
+            lang,
+            project_name,
+            template,
+            scaffold,
+        } => {
+            let opts = scaffold.into_options(cli.yes);
+            if let Some(template) = template {
+                let (lang, project_name) = match (lang, project_name) {
+                    (Some(name), None) => (None, Some(name)),
+                    (lang, name) => (lang, name),
+                };
+                let lang = match lang {
+                    Some(lang) => {
+                        Some(ProgrammingLanguage::from_name(&lang).ok_or_else(|| {
+                            color_eyre::eyre::eyre!("Unknown language '{}'", lang)
+                        })?)
+                    }
+                    None => None,
+                };
+                return scaffold::from_template::setup(
+                    &template,
+                    lang,
+                    project_name.as_deref(),
+                    &opts,
+                    prompter.as_mut(),
+                );
             }
-            if lang.as_deref() != Some("rust") {
-                not_implemented_warning::show(format!(
-                    "Scaffolding for language '{}' is not implemented yet.",
-                    lang.as_deref().expect("Language is required")
-                ))?;
-                return Ok(());
+            if let Some(framework) = opts
+                .framework
+                .as_deref()
+                .filter(|name| !name.eq_ignore_ascii_case("none"))
+            {
+                // The framework implies the language, so a lone positional is the project name.
+                let (lang, project_name) = match (lang, project_name) {
+                    (Some(name), None) => (None, Some(name)),
+                    (lang, name) => (lang, name),
+                };
+                let lang = match lang {
+                    Some(lang) => ProgrammingLanguage::from_name(&lang)
+                        .ok_or_else(|| color_eyre::eyre::eyre!("Unknown language '{}'", lang))?,
+                    None => scaffold::framework::language(framework)?,
+                };
+                return scaffold::setup(lang, project_name.as_deref(), &opts, prompter.as_mut());
             }
-            let name = match project_name {
-                Some(n) => n.trim().to_string(),
-                None => {
-                    print!("Project name? (press Enter to use current directory): ");
-                    io::stdout().flush()?;
-                    let mut input = String::new();
-                    io::stdin().read_line(&mut input)?;
-                    input.trim().to_string()
-                }
+            let Some(lang) = lang else {
+                interactive_setup::show(&opts, prompter.as_mut())?;
+                return Ok(());
             };
-            if name.is_empty() {
-                let status = Command::new("cargo").arg("init").status()?;
-                if !status.success() {
-                    eprintln!("Failed to run cargo init in current directory");
-                }
-            } else {
-                std::fs::create_dir_all(&name)?;
-                let status = Command::new("cargo")
-                    .args(["init", "--name", &name, &name])
-                    .status()?;
-                if !status.success() {
-                    eprintln!("Failed to run cargo init");
+            match ProgrammingLanguage::from_name(&lang) {
+                Some(lang) => {
+                    scaffold::setup(lang, project_name.as_deref(), &opts, prompter.as_mut())?
                 }
+                None => not_implemented_warning::show(format!("Unknown language '{}'.", lang))?,
             }
         }
         Commands::Settings => {
@@ -78,14 +312,76 @@ fn main() -> color_eyre::Result<()> {
             println!("Settings stored at: {:?}", file_path_str);
         }
         Commands::Gallery => {
-            ui::gallery::show()?;
+            let opts = ScaffoldOptions {
+                assume_yes: cli.yes,
+                ..ScaffoldOptions::default()
+            };
+            ui::gallery::show(&opts, prompter.as_mut())?;
+        }
+        Commands::Templates => {
+            for template in template::all()? {
+                let source = match &template.source {
+                    template::TemplateSource::Builtin => "built-in".to_string(),
+                    template::TemplateSource::Directory(path) => path.display().to_string(),
+                    template::TemplateSource::Git(url) => url.clone(),
+                };
+                println!(
+                    "{:<20} {:<10} {} ({})",
+                    template.manifest.name,
+                    template.manifest.language.as_deref().unwrap_or("-"),
+                    template.manifest.description,
+                    source
+                );
+            }
+            if let Some(dir) = template::user_templates_dir() {
+                println!("\nUser templates are read from: {}", dir.display());
+            }
+        }
+        Commands::Tools { command } => match command {
+            ToolsCommand::History => install::history()?,
+            ToolsCommand::Install {
+                toolchain,
+                version,
+                dry_run,
+            } => {
+                let opts = ScaffoldOptions {
+                    assume_yes: cli.yes,
+                    dry_run,
+                    ..ScaffoldOptions::default()
+                };
+                install::install_toolchain(
+                    &toolchain,
+                    version.as_deref(),
+                    true,
+                    &opts,
+                    prompter.as_mut(),
+                )?;
+            }
+            ToolsCommand::Remove { tool, dry_run } => {
+                let opts = ScaffoldOptions {
+                    assume_yes: cli.yes,
+                    dry_run,
+                    ..ScaffoldOptions::default()
+                };
+                install::remove(&tool, &opts, prompter.as_mut())?;
+            }
+        },
+        Commands::Doctor { json } => {
+            if doctor::run(json)? > 0 {
+                std::process::exit(1);
+            }
+        }
+        Commands::Env => {
+            for line in install::toolchain::env_lines()? {
+                println!("{}", line);
+            }
         }
         Commands::Add => {
             println!("Add command is not implemented yet.");
         }
         Commands::Clear => {
-            if yn::ask("Are you sure you want to clear the database?")? {
-                database::manage::clear()?;
+            if prompter.confirm("Are you sure you want to clear the database?")? {
+                database::manage::clear(prompter.as_mut())?;
             } else {
                 println!("Aborted clearing the database.");
             }
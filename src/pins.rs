@@ -0,0 +1,570 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use semver::Version;
+
+use crate::install;
+use crate::install::plan::InstallPlan;
+use crate::probe::{self, ToolInfo};
+use crate::scaffold::ScaffoldOptions;
+use crate::state::languages::ProgrammingLanguage;
+use crate::ui::prompt::Prompter;
+use crate::util::is_on_path;
+
+/// A file that pins toolchain versions for the directory it's in and everything below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinFile {
+    /// `mise.toml` or `.mise.toml`, with a `[tools]` table.
+    Mise,
+    /// asdf's `.tool-versions`, which mise reads as well.
+    ToolVersions,
+    /// rustup's `rust-toolchain.toml`.
+    RustToolchain,
+    /// pyenv's `.python-version`.
+    PythonVersion,
+    /// nvm's `.nvmrc`.
+    Nvmrc,
+}
+
+/// The files checked in each directory, with the first one pinning a tool winning, as mise
+/// does.
+const PIN_FILES: [(&str, PinFile); 6] = [
+    ("mise.toml", PinFile::Mise),
+    (".mise.toml", PinFile::Mise),
+    (".tool-versions", PinFile::ToolVersions),
+    ("rust-toolchain.toml", PinFile::RustToolchain),
+    (".python-version", PinFile::PythonVersion),
+    (".nvmrc", PinFile::Nvmrc),
+];
+
+impl PinFile {
+    /// The version managers that can install what this file pins, in order of preference.
+    fn managers(self) -> &'static [&'static str] {
+        match self {
+            PinFile::Mise => &["mise"],
+            PinFile::ToolVersions => &["asdf", "mise"],
+            PinFile::RustToolchain => &["rustup"],
+            PinFile::PythonVersion => &["pyenv", "mise", "asdf"],
+            PinFile::Nvmrc => &["mise", "asdf"],
+        }
+    }
+}
+
+/// A toolchain projector knows how to check, under the names version managers give it.
+struct PinnedTool {
+    /// mise's name for it, which is also the id in the install history.
+    name: &'static str,
+    /// Other names pin files use for it.
+    aliases: &'static [&'static str],
+    /// asdf's plugin for it.
+    plugin: &'static str,
+    /// The binary that's probed for the installed version.
+    binary: &'static str,
+    languages: &'static [ProgrammingLanguage],
+}
+
+const PINNED_TOOLS: [PinnedTool; 5] = [
+    PinnedTool {
+        name: "rust",
+        aliases: &[],
+        plugin: "rust",
+        binary: "rustc",
+        languages: &[ProgrammingLanguage::Rust],
+    },
+    PinnedTool {
+        name: "python",
+        aliases: &[],
+        plugin: "python",
+        binary: "python3",
+        languages: &[ProgrammingLanguage::Python],
+    },
+    PinnedTool {
+        name: "node",
+        aliases: &["nodejs"],
+        plugin: "nodejs",
+        binary: "node",
+        languages: &[ProgrammingLanguage::JavaScript],
+    },
+    PinnedTool {
+        name: "go",
+        aliases: &["golang"],
+        plugin: "golang",
+        binary: "go",
+        languages: &[ProgrammingLanguage::Go],
+    },
+    PinnedTool {
+        name: "java",
+        aliases: &[],
+        plugin: "java",
+        binary: "java",
+        languages: &[ProgrammingLanguage::Java, ProgrammingLanguage::Kotlin],
+    },
+];
+
+fn pinned_tool(name: &str) -> Option<&'static PinnedTool> {
+    PINNED_TOOLS
+        .iter()
+        .find(|tool| tool.name == name || tool.aliases.contains(&name))
+}
+
+/// A toolchain version pinned by a file.
+#[derive(Debug, Clone)]
+pub struct Pin {
+    /// The tool as the file names it, e.g. `nodejs` in `.tool-versions`.
+    pub tool: String,
+    pub version: String,
+    pub file: PathBuf,
+    pub kind: PinFile,
+}
+
+impl Pin {
+    /// The binary that's probed for this pin, if projector knows the tool.
+    pub fn binary(&self) -> Option<&'static str> {
+        pinned_tool(&self.tool).map(|tool| tool.binary)
+    }
+
+    /// Whether the installed `version` is the pinned one. Pins are prefixes, so `3.12` accepts
+    /// any 3.12 release. `None` when the pin isn't a version number, e.g. `stable`, `lts/iron`
+    /// or `latest`, so there's nothing to compare.
+    pub fn matches(&self, version: &Version) -> Option<bool> {
+        let pinned = self.version.trim().trim_start_matches('v');
+        // Vendor prefixes, like `temurin-21.0.2+13.0.LTS` for Java.
+        let pinned = match pinned.split_once('-') {
+            Some((vendor, rest))
+                if vendor.chars().all(|c| c.is_ascii_alphabetic())
+                    && !["stable", "beta", "nightly"].contains(&vendor) =>
+            {
+                rest
+            }
+            _ => pinned,
+        };
+        if !pinned.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let numeric: String = pinned
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        let installed = [version.major, version.minor, version.patch];
+        let parts = numeric
+            .trim_end_matches('.')
+            .split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        Some(
+            parts
+                .iter()
+                .zip(installed.iter())
+                .all(|(pinned, installed)| pinned == installed),
+        )
+    }
+}
+
+/// A pinned tool that isn't installed at the pinned version.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub pin: Pin,
+    /// What's on the PATH instead, or `None` if the tool isn't installed at all.
+    pub installed: Option<ToolInfo>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} pins {} {}, but ",
+            self.pin.file.display(),
+            self.pin.tool,
+            self.pin.version
+        )?;
+        match &self.installed {
+            Some(info) => write!(
+                f,
+                "{} {} is installed at {}",
+                info.name,
+                info.version
+                    .as_ref()
+                    .map(Version::to_string)
+                    .unwrap_or_default(),
+                info.path.display()
+            ),
+            None => write!(
+                f,
+                "{} isn't installed",
+                self.pin.binary().unwrap_or(&self.pin.tool)
+            ),
+        }
+    }
+}
+
+/// Every version pinned for `dir`, looking in it and each of its parents. A pin in a nearer
+/// directory wins over one further up. `dir` doesn't have to exist yet.
+pub fn find(dir: &Path) -> Vec<Pin> {
+    let mut pins: Vec<Pin> = Vec::new();
+    for dir in dir.ancestors() {
+        for (file_name, kind) in PIN_FILES {
+            let file = dir.join(file_name);
+            let Ok(contents) = fs::read_to_string(&file) else {
+                continue;
+            };
+            for (tool, version) in parse(kind, &contents) {
+                let same_tool = |pin: &Pin| match (pinned_tool(&pin.tool), pinned_tool(&tool)) {
+                    (Some(a), Some(b)) => a.name == b.name,
+                    _ => pin.tool == tool,
+                };
+                if !pins.iter().any(same_tool) {
+                    pins.push(Pin {
+                        tool,
+                        version,
+                        file: file.clone(),
+                        kind,
+                    });
+                }
+            }
+        }
+    }
+    pins
+}
+
+/// The tools and versions a pin file lists. Tools with several versions count as their first.
+fn parse(kind: PinFile, contents: &str) -> Vec<(String, String)> {
+    let first_line = || {
+        contents
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    };
+    match kind {
+        PinFile::ToolVersions => contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('#').next()?.split_whitespace();
+                Some((fields.next()?.to_string(), fields.next()?.to_string()))
+            })
+            .collect(),
+        PinFile::Mise => {
+            let Ok(table) = contents.parse::<toml::Table>() else {
+                return Vec::new();
+            };
+            let Some(tools) = table.get("tools").and_then(toml::Value::as_table) else {
+                return Vec::new();
+            };
+            tools
+                .iter()
+                .filter_map(|(tool, value)| {
+                    let version = match value {
+                        toml::Value::String(version) => version.clone(),
+                        toml::Value::Array(versions) => versions.first()?.as_str()?.to_string(),
+                        toml::Value::Table(options) => {
+                            options.get("version")?.as_str()?.to_string()
+                        }
+                        _ => return None,
+                    };
+                    Some((tool.clone(), version))
+                })
+                .collect()
+        }
+        PinFile::RustToolchain => contents
+            .parse::<toml::Table>()
+            .ok()
+            .and_then(|table| {
+                let channel = table.get("toolchain")?.get("channel")?.as_str()?;
+                Some(vec![("rust".to_string(), channel.to_string())])
+            })
+            .unwrap_or_default(),
+        PinFile::PythonVersion => first_line()
+            .map(|version| vec![("python".to_string(), version)])
+            .unwrap_or_default(),
+        PinFile::Nvmrc => first_line()
+            .map(|version| vec![("node".to_string(), version)])
+            .unwrap_or_default(),
+    }
+}
+
+/// The pins for `dir` that what's installed doesn't satisfy, limited to `lang`'s tools if
+/// given. Tools projector doesn't know, `system` pins and tools whose version can't be told are
+/// skipped.
+pub fn mismatches(dir: &Path, lang: Option<ProgrammingLanguage>) -> Vec<Mismatch> {
+    find(dir)
+        .into_iter()
+        .filter(|pin| {
+            pinned_tool(&pin.tool).is_some_and(|tool| {
+                lang.is_none_or(|lang| tool.languages.contains(&lang)) && pin.version != "system"
+            })
+        })
+        .filter_map(|pin| mismatch(pin, dir))
+        .collect()
+}
+
+fn mismatch(pin: Pin, dir: &Path) -> Option<Mismatch> {
+    let installed = probe::probe_in(pin.binary()?, dir);
+    (!satisfies(&pin, installed.as_ref())).then_some(Mismatch { pin, installed })
+}
+
+/// Whether `installed` is good enough for `pin`. A tool whose version can't be told, or a pin
+/// that isn't a version number, is given the benefit of the doubt.
+fn satisfies(pin: &Pin, installed: Option<&ToolInfo>) -> bool {
+    match installed {
+        Some(info) => info
+            .version
+            .as_ref()
+            .is_none_or(|version| pin.matches(version) != Some(false)),
+        None => false,
+    }
+}
+
+/// The command installing the pinned version with the first of the pin file's version managers
+/// that's on the PATH, e.g. `mise install node@20` or `rustup toolchain install 1.80`.
+pub fn install_command(pin: &Pin) -> Option<Vec<String>> {
+    let tool = pinned_tool(&pin.tool)?;
+    let manager = pin
+        .kind
+        .managers()
+        .iter()
+        .find(|manager| is_on_path(manager))?;
+    let spec = format!("{}@{}", tool.name, pin.version);
+    let command: Vec<&str> = match *manager {
+        "mise" => vec!["mise", "install", &spec],
+        "asdf" => vec!["asdf", "install", tool.plugin, &pin.version],
+        "rustup" => vec!["rustup", "toolchain", "install", &pin.version],
+        "pyenv" => vec!["pyenv", "install", "--skip-existing", &pin.version],
+        _ => return None,
+    };
+    Some(command.into_iter().map(str::to_string).collect())
+}
+
+/// A plan running [`install_command`].
+pub fn install_plan(pin: &Pin) -> Option<InstallPlan> {
+    let tool = pinned_tool(&pin.tool)?;
+    let command = install_command(pin)?;
+    let mut plan = InstallPlan::new(tool.name, format!("{} {}", tool.name, pin.version));
+    plan.method = command[0].clone();
+    plan.version = Some(pin.version.clone());
+    plan.run(
+        &command.iter().map(String::as_str).collect::<Vec<_>>(),
+        false,
+    );
+    Some(plan)
+}
+
+/// Warns about each tool that doesn't match the version pinned for `dir`, limited to `lang`'s
+/// tools if given, and offers to install the pinned version. Mismatches don't stop anything;
+/// declined by default with `--yes`.
+pub fn check(
+    dir: &Path,
+    lang: Option<ProgrammingLanguage>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    for mismatch in mismatches(dir, lang) {
+        prompter.suspend();
+        eprintln!("Warning: {}.", mismatch);
+        let Some(plan) = install_plan(&mismatch.pin) else {
+            let managers = mismatch.pin.kind.managers().join(" or ");
+            eprintln!(
+                "Install {} {} yourself; projector can do it when {} is on the PATH.",
+                mismatch.pin.tool, mismatch.pin.version, managers
+            );
+            continue;
+        };
+        if install::confirm_and_run(&plan, opts, prompter)?
+            && self::mismatch(mismatch.pin.clone(), dir).is_some()
+        {
+            eprintln!(
+                "Warning: {} is installed now, but isn't the one on the PATH here. Activate it with {}, e.g. through its shims.",
+                plan.what, plan.method
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(tool, version)| (tool.to_string(), version.to_string()))
+            .collect()
+    }
+
+    fn pin(tool: &str, version: &str) -> Pin {
+        Pin {
+            tool: tool.to_string(),
+            version: version.to_string(),
+            file: PathBuf::from(".tool-versions"),
+            kind: PinFile::ToolVersions,
+        }
+    }
+
+    #[test]
+    fn pin_files_are_parsed() {
+        // The file's kind and contents, and the tools and versions read from it.
+        type Case = (
+            PinFile,
+            &'static str,
+            &'static [(&'static str, &'static str)],
+        );
+        let cases: [Case; 14] = [
+            (
+                PinFile::ToolVersions,
+                "# tools\nnodejs 20.11.0 18.19.0 # newest first\n\npython 3.12.1\ngolang\n",
+                &[("nodejs", "20.11.0"), ("python", "3.12.1")],
+            ),
+            (PinFile::ToolVersions, "", &[]),
+            (
+                PinFile::Mise,
+                "[env]\nA = \"b\"\n\n[tools]\nnode = \"lts\"\npython = [\"3.12\", \"3.11\"]\ngo = { version = \"1.22\" }\njava = 21\n",
+                &[("go", "1.22"), ("node", "lts"), ("python", "3.12")],
+            ),
+            (PinFile::Mise, "[env]\nA = \"b\"\n", &[]),
+            (PinFile::Mise, "[tools\n", &[]),
+            (
+                PinFile::RustToolchain,
+                "[toolchain]\nchannel = \"1.80.1\"\ncomponents = [\"clippy\"]\n",
+                &[("rust", "1.80.1")],
+            ),
+            (
+                PinFile::RustToolchain,
+                "[toolchain]\nchannel = \"nightly-2024-01-01\"\n",
+                &[("rust", "nightly-2024-01-01")],
+            ),
+            (
+                PinFile::RustToolchain,
+                "[toolchain]\ncomponents = [\"rustfmt\"]\n",
+                &[],
+            ),
+            (
+                PinFile::PythonVersion,
+                "# pyenv\n\n3.12.1\n3.11.7\n",
+                &[("python", "3.12.1")],
+            ),
+            (PinFile::PythonVersion, "pypy3.10 # alt\n", &[("python", "pypy3.10")]),
+            (PinFile::PythonVersion, "\n# only a comment\n", &[]),
+            (PinFile::Nvmrc, "lts/*\n", &[("node", "lts/*")]),
+            (PinFile::Nvmrc, "v20.11.0 # iron\n", &[("node", "v20.11.0")]),
+            (PinFile::Nvmrc, "  20\r\n", &[("node", "20")]),
+        ];
+        for (kind, contents, expected) in cases {
+            assert_eq!(
+                parse(kind, contents),
+                pairs(expected),
+                "{:?}: {:?}",
+                kind,
+                contents
+            );
+        }
+    }
+
+    #[test]
+    fn pins_match_by_prefix() {
+        let cases = [
+            ("20", "20.11.0", Some(true)),
+            ("v20.11.0", "20.11.0", Some(true)),
+            ("v20.11.1", "20.11.0", Some(false)),
+            ("3.12", "3.12.4", Some(true)),
+            ("3.12", "3.11.9", Some(false)),
+            ("3.12.", "3.12.4", Some(true)),
+            ("3.13.0rc1", "3.13.0", Some(true)),
+            ("1.80", "1.81.0", Some(false)),
+            ("temurin-21.0.2+13.0.LTS", "21.0.2", Some(true)),
+            ("temurin-17", "21.0.2", Some(false)),
+            ("lts/*", "20.11.0", None),
+            ("lts/iron", "20.11.0", None),
+            ("stable", "1.80.0", None),
+            ("nightly-2024-01-01", "1.77.0", None),
+            ("latest", "1.22.0", None),
+            ("system", "3.12.0", None),
+        ];
+        for (pinned, installed, expected) in cases {
+            let version = Version::parse(installed).unwrap();
+            assert_eq!(
+                pin("node", pinned).matches(&version),
+                expected,
+                "{} against {}",
+                pinned,
+                installed
+            );
+        }
+    }
+
+    #[test]
+    fn mismatches_are_what_the_pin_rules_out() {
+        let info = |version: Option<&str>| ToolInfo {
+            name: "node".to_string(),
+            path: PathBuf::from("/usr/bin/node"),
+            version: version.map(|version| Version::parse(version).unwrap()),
+        };
+        let cases = [
+            ("20", Some(info(Some("20.11.0"))), true),
+            ("20", Some(info(Some("18.19.0"))), false),
+            ("lts/*", Some(info(Some("18.19.0"))), true),
+            ("20", Some(info(None)), true),
+            ("20", None, false),
+        ];
+        for (pinned, installed, satisfied) in cases {
+            assert_eq!(
+                satisfies(&pin("node", pinned), installed.as_ref()),
+                satisfied,
+                "{} against {:?}",
+                pinned,
+                installed.as_ref().map(|info| &info.version)
+            );
+        }
+
+        let mismatch = Mismatch {
+            pin: pin("nodejs", "20"),
+            installed: Some(info(Some("18.19.0"))),
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            ".tool-versions pins nodejs 20, but node 18.19.0 is installed at /usr/bin/node"
+        );
+        let missing = Mismatch {
+            pin: pin("nodejs", "20"),
+            installed: None,
+        };
+        assert_eq!(
+            missing.to_string(),
+            ".tool-versions pins nodejs 20, but node isn't installed"
+        );
+    }
+
+    #[test]
+    fn nearer_and_earlier_pins_win() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let app = root.join("app");
+        fs::create_dir(&app).unwrap();
+        fs::write(
+            root.join(".tool-versions"),
+            "nodejs 18.19.0\ngolang 1.21.0\nterraform 1.7.0\n",
+        )
+        .unwrap();
+        fs::write(root.join(".python-version"), "3.11\n").unwrap();
+        fs::write(app.join(".nvmrc"), "20\n").unwrap();
+        fs::write(app.join(".tool-versions"), "python 3.12.1\n").unwrap();
+        fs::write(app.join("mise.toml"), "[tools]\npython = \"3.13\"\n").unwrap();
+
+        // The project directory doesn't exist yet; its parents' pins still apply.
+        let pins = find(&app.join("new"));
+        let found: Vec<(&str, &str, PathBuf)> = pins
+            .iter()
+            .filter(|pin| pin.file.starts_with(root))
+            .map(|pin| (pin.tool.as_str(), pin.version.as_str(), pin.file.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("python", "3.13", app.join("mise.toml")),
+                ("node", "20", app.join(".nvmrc")),
+                ("golang", "1.21.0", root.join(".tool-versions")),
+                ("terraform", "1.7.0", root.join(".tool-versions")),
+            ]
+        );
+    }
+}
@@ -1,10 +1,11 @@
+use color_eyre::eyre::eyre;
 use serde::{Deserialize, Serialize};
-use std::env;
-use std::fs;
+use serde_json::Value;
 use std::path::Path;
-use std::process::Command;
 
-use crate::ui::{not_implemented_warning, yn};
+use crate::install::{self, download::Downloader, plan::InstallPlan, Tool};
+use crate::scaffold::ScaffoldOptions;
+use crate::ui::{not_implemented_warning, prompt::Prompter};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ValidEditors {
@@ -29,7 +30,14 @@ impl Default for Config {
     }
 }
 
-pub fn run_editor_setup(project_dir: &Path) -> color_eyre::Result<()> {
+/// Opens `project_dir` in the configured editor if that's enabled in the settings, offering to
+/// install the editor when it's missing. The install follows `opts`, so `--yes` declines it and
+/// `--dry-run` only prints the plan.
+pub fn run_editor_setup(
+    project_dir: &Path,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
     let cfg: Config = confy::load("projector", None)?;
     println!("Config loaded: {:?}", cfg); // Debug print
 
@@ -93,71 +101,17 @@ pub fn run_editor_setup(project_dir: &Path) -> color_eyre::Result<()> {
                     println!("VS Code opened successfully."); // Debug print
                 }
             } else {
-                let choice =
-                    yn::ask("VS Code is not installed. Would you like to install it? (y/n)")
-                        .expect("Failed to get user input");
-                if choice {
-                    if cfg!(target_os = "windows") {
-                        let temp_dir = env::temp_dir().join("vscode_install");
-                        if temp_dir.exists() {
-                            fs::remove_dir_all(&temp_dir)
-                                .expect("Failed to remove existing temp directory");
-                        }
-                        fs::create_dir_all(&temp_dir).expect("Failed to create temp directory");
-
-                        let vscode_installer_path = temp_dir.join("VSCodeSetup.exe");
-                        Command::new("curl")
-                            .args(&[
-                                "-o",
-                                vscode_installer_path.to_str().unwrap(),
-                                "https://code.visualstudio.com/sha/download?build=stable&os=win32-x64-user",
-                            ])
-                            .status()
-                            .expect("Failed to download VS Code installer");
-
-                        Command::new(vscode_installer_path)
-                            .status()
-                            .expect("Failed to execute VS Code installer");
-
-                        fs::remove_dir_all(&temp_dir).expect("Failed to remove temp directory");
-                    } else {
-                        let hostname = hostname::get().unwrap_or_default();
-                        match hostname.to_string_lossy().as_ref() {
-                            "debian" | "ubuntu" => {
-                                Command::new("sudo")
-                                    .arg("apt")
-                                    .arg("install")
-                                    .arg("-y")
-                                    .arg("code")
-                                    .status()
-                                    .expect("Failed to install VS Code using apt");
-                            }
-                            "fedora" => {
-                                Command::new("sudo")
-                                    .arg("dnf")
-                                    .arg("install")
-                                    .arg("-y")
-                                    .arg("code")
-                                    .status()
-                                    .expect("Failed to install VS Code using dnf");
-                            }
-                            "arch" => {
-                                Command::new("sudo")
-                                    .arg("pacman")
-                                    .arg("-S")
-                                    .arg("--noconfirm")
-                                    .arg("code")
-                                    .status()
-                                    .expect("Failed to install VS Code using pacman");
-                            }
-                            _ => {
-                                println!("Please install VS Code using your package manager. For example:");
-                                println!("Debian/Ubuntu: sudo apt install code");
-                                println!("Fedora: sudo dnf install code");
-                                println!("Arch: sudo pacman -S code");
-                            }
-                        }
+                println!("VS Code is not installed.");
+                let plan = if cfg!(target_os = "windows") {
+                    vscode_installer_plan()
+                } else {
+                    InstallPlan::packages(&[Tool::Code])
+                };
+                match plan {
+                    Ok(plan) => {
+                        install::confirm_and_run(&plan, opts, prompter)?;
                     }
+                    Err(e) => eprintln!("Error: {}", e),
                 }
             }
         }
@@ -169,3 +123,30 @@ pub fn run_editor_setup(project_dir: &Path) -> color_eyre::Result<()> {
 
     Ok(())
 }
+
+/// Plans running the latest stable VS Code user installer, as published by VS Code's update
+/// service along with its checksum.
+fn vscode_installer_plan() -> color_eyre::Result<InstallPlan> {
+    let arch = if cfg!(target_arch = "aarch64") {
+        "arm64"
+    } else {
+        "x64"
+    };
+    let latest: Value = serde_json::from_str(&Downloader::new()?.text(&format!(
+        "https://update.code.visualstudio.com/api/update/win32-{}-user/stable/latest",
+        arch
+    ))?)?;
+    let field = |key: &str| {
+        latest[key]
+            .as_str()
+            .ok_or_else(|| eyre!("VS Code's update service gave no '{}'", key))
+    };
+    let mut plan = InstallPlan::new("code", format!("VS Code {}", field("name")?));
+    let installer = plan.download_verified(
+        field("url")?,
+        "VSCodeUserSetup.exe",
+        Some(field("sha256hash")?),
+    )?;
+    plan.run(&[&installer.to_string_lossy()], false);
+    Ok(plan)
+}
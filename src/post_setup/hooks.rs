@@ -0,0 +1,284 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{bail, eyre};
+use serde::{Deserialize, Serialize};
+
+use crate::state::app::Config;
+use crate::state::languages::ProgrammingLanguage;
+
+/// When a hook runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Before anything is written; the project directory may not exist yet.
+    PreScaffold,
+    /// After the project's files are in place, before it's registered in the database.
+    PostScaffold,
+    /// Before the project is opened in the editor.
+    PreOpen,
+    /// After the editor has been started.
+    PostOpen,
+}
+
+impl Stage {
+    /// The stage's key in the config, also passed to hooks as `PROJECTOR_HOOK`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Stage::PreScaffold => "pre_scaffold",
+            Stage::PostScaffold => "post_scaffold",
+            Stage::PreOpen => "pre_open",
+            Stage::PostOpen => "post_open",
+        }
+    }
+}
+
+/// What a failing hook does to the rest of the run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFailure {
+    /// Print a warning and carry on.
+    #[default]
+    Warn,
+    /// Stop. A failing scaffold hook rolls the project back like any other failed step.
+    Abort,
+}
+
+/// A command run at one of the [`Stage`]s, e.g. to add license headers or install pre-commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hook {
+    /// Run with `sh -c` (`cmd /C` on Windows) from the project directory, with its path, name
+    /// and language in `PROJECTOR_PROJECT_PATH`, `PROJECTOR_PROJECT_NAME` and
+    /// `PROJECTOR_PROJECT_LANGUAGE`. Stdin is closed, so hooks can't prompt.
+    pub command: String,
+    /// Seconds before the hook is stopped and counted as failed; 0 waits forever.
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub on_failure: OnFailure,
+}
+
+fn default_timeout() -> u64 {
+    300
+}
+
+/// Hooks for each stage, run in the order they're listed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub pre_scaffold: Vec<Hook>,
+    pub post_scaffold: Vec<Hook>,
+    pub pre_open: Vec<Hook>,
+    pub post_open: Vec<Hook>,
+}
+
+impl Hooks {
+    fn stage(&self, stage: Stage) -> &[Hook] {
+        match stage {
+            Stage::PreScaffold => &self.pre_scaffold,
+            Stage::PostScaffold => &self.post_scaffold,
+            Stage::PreOpen => &self.pre_open,
+            Stage::PostOpen => &self.post_open,
+        }
+    }
+}
+
+/// The hooks to run at `stage` for a `lang` project: the global ones from `[hooks]`, then the
+/// ones from `[language_hooks.<lang>]`.
+pub fn configured(stage: Stage, lang: ProgrammingLanguage) -> Vec<Hook> {
+    let cfg: Config = confy::load("projector", None).unwrap_or_default();
+    let mut hooks = cfg.hooks.stage(stage).to_vec();
+    for (name, language_hooks) in &cfg.language_hooks {
+        if ProgrammingLanguage::from_name(name) == Some(lang) {
+            hooks.extend_from_slice(language_hooks.stage(stage));
+        }
+    }
+    hooks
+}
+
+impl Hook {
+    /// How the hook shows up in plans and messages.
+    pub fn description(&self, stage: Stage) -> String {
+        format!("Run the {} hook `{}`", stage.id(), self.command)
+    }
+
+    /// Runs the hook for the `lang` project at `project_dir`. If the directory doesn't exist
+    /// yet, the hook runs from the nearest parent that does.
+    pub fn run(
+        &self,
+        stage: Stage,
+        project_dir: &Path,
+        lang: ProgrammingLanguage,
+    ) -> color_eyre::Result<()> {
+        let cwd = project_dir
+            .ancestors()
+            .find(|dir| dir.is_dir())
+            .unwrap_or(project_dir);
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        let name = project_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        command
+            .arg(&self.command)
+            .current_dir(cwd)
+            .stdin(Stdio::null())
+            .env("PROJECTOR_HOOK", stage.id())
+            .env("PROJECTOR_PROJECT_PATH", project_dir)
+            .env("PROJECTOR_PROJECT_NAME", name)
+            .env(
+                "PROJECTOR_PROJECT_LANGUAGE",
+                format!("{:?}", lang).to_lowercase(),
+            );
+        let mut child = command
+            .spawn()
+            .map_err(|e| eyre!("couldn't start the hook: {}", e))?;
+        let deadline = (self.timeout_secs > 0)
+            .then(|| Instant::now() + Duration::from_secs(self.timeout_secs));
+        loop {
+            if let Some(status) = child.try_wait()? {
+                if !status.success() {
+                    bail!("the hook exited with {}", status);
+                }
+                return Ok(());
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                // Best effort; the hook's own children may outlive it.
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!(
+                    "the hook didn't finish within {} seconds and was stopped",
+                    self.timeout_secs
+                );
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+/// Runs the hooks for `stage`, see [`configured`] and [`run_each`].
+pub fn run_all(
+    stage: Stage,
+    project_dir: &Path,
+    lang: ProgrammingLanguage,
+) -> color_eyre::Result<()> {
+    run_each(&configured(stage, lang), stage, project_dir, lang)
+}
+
+/// Runs `hooks` in order. Failures of `warn` hooks are printed; the first failing `abort` hook
+/// stops the rest and is returned as the error.
+fn run_each(
+    hooks: &[Hook],
+    stage: Stage,
+    project_dir: &Path,
+    lang: ProgrammingLanguage,
+) -> color_eyre::Result<()> {
+    for hook in hooks {
+        if let Err(e) = hook.run(stage, project_dir, lang) {
+            match hook.on_failure {
+                OnFailure::Warn => {
+                    eprintln!("Warning: {} failed: {}", hook.description(stage), e)
+                }
+                OnFailure::Abort => bail!("{} failed: {}", hook.description(stage), e),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn hook(command: &str, on_failure: OnFailure) -> Hook {
+        Hook {
+            command: command.to_string(),
+            timeout_secs: 10,
+            on_failure,
+        }
+    }
+
+    #[test]
+    fn failing_command_fails_the_hook() {
+        let temp = tempfile::tempdir().unwrap();
+        let error = hook("exit 1", OnFailure::Warn)
+            .run(Stage::PostScaffold, temp.path(), ProgrammingLanguage::Rust)
+            .unwrap_err();
+        assert!(error.to_string().contains("exited with"), "{}", error);
+    }
+
+    #[test]
+    fn slow_hooks_are_stopped() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut slow = hook("sleep 30", OnFailure::Warn);
+        slow.timeout_secs = 1;
+        let start = Instant::now();
+        let error = slow
+            .run(Stage::PostScaffold, temp.path(), ProgrammingLanguage::Rust)
+            .unwrap_err();
+        assert!(error.to_string().contains("within 1 seconds"), "{}", error);
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn hooks_see_the_project() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("my-app");
+        fs::create_dir(&dir).unwrap();
+        hook(
+            "echo \"$PROJECTOR_PROJECT_NAME $PROJECTOR_PROJECT_LANGUAGE $PROJECTOR_HOOK $PROJECTOR_PROJECT_PATH\" > env.txt",
+            OnFailure::Abort,
+        )
+        .run(Stage::PostScaffold, &dir, ProgrammingLanguage::Python)
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("env.txt")).unwrap(),
+            format!("my-app python post_scaffold {}\n", dir.display())
+        );
+    }
+
+    #[test]
+    fn hooks_run_from_the_nearest_existing_parent() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("new/my-app");
+        hook("pwd > pwd.txt", OnFailure::Abort)
+            .run(Stage::PreScaffold, &dir, ProgrammingLanguage::Rust)
+            .unwrap();
+        let pwd = fs::read_to_string(temp.path().join("pwd.txt")).unwrap();
+        assert_eq!(
+            fs::canonicalize(pwd.trim()).unwrap(),
+            fs::canonicalize(temp.path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn warn_hooks_carry_on_and_abort_hooks_stop_the_rest() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let warned = [
+            hook("exit 1", OnFailure::Warn),
+            hook("touch after-warn", OnFailure::Abort),
+        ];
+        run_each(&warned, Stage::PreOpen, dir, ProgrammingLanguage::Go).unwrap();
+        assert!(dir.join("after-warn").exists());
+
+        let aborted = [
+            hook("exit 3", OnFailure::Abort),
+            hook("touch after-abort", OnFailure::Warn),
+        ];
+        let error = run_each(&aborted, Stage::PreOpen, dir, ProgrammingLanguage::Go).unwrap_err();
+        assert!(error.to_string().contains("`exit 3` failed"), "{}", error);
+        assert!(!dir.join("after-abort").exists());
+    }
+}
@@ -1,11 +1,22 @@
-use crate::database::{manage::setup_database, model::Project};
+use std::path::Path;
 
-pub mod editor;
+use crate::scaffold::ScaffoldOptions;
+use crate::state::languages::ProgrammingLanguage;
+use crate::ui::prompt::Prompter;
 
-pub fn run_post_setup(project: &mut Project) -> color_eyre::Result<()> {
-    editor::run_editor_setup(std::path::Path::new(&project.path))?;
+pub mod editor;
+pub mod hooks;
 
-    // Add project to database
-    project.insert(&setup_database()?)?;
-    Ok(())
+/// Opens the `lang` project at `project_dir` in the editor, running the `pre_open` and
+/// `post_open` hooks around it. An `abort` hook failing before the editor starts keeps it
+/// closed. `opts` decides how installing a missing editor is confirmed.
+pub fn open(
+    project_dir: &Path,
+    lang: ProgrammingLanguage,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    hooks::run_all(hooks::Stage::PreOpen, project_dir, lang)?;
+    editor::run_editor_setup(project_dir, opts, prompter)?;
+    hooks::run_all(hooks::Stage::PostOpen, project_dir, lang)
 }
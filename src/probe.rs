@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use color_eyre::eyre::eyre;
+use semver::Version;
+
+use crate::state::app::Config;
+use crate::state::languages::ProgrammingLanguage;
+use crate::util::which;
+
+/// What probing a tool found: where it is and which version it reports.
+#[derive(Debug, Clone)]
+pub struct ToolInfo {
+    pub name: String,
+    pub path: PathBuf,
+    /// The reported version, padded to three components, e.g. `3.12.0` for Python 3.12. `None`
+    /// if the tool ran but its output had no version in it.
+    pub version: Option<Version>,
+}
+
+/// How a tool's probe is faked through `PROJECTOR_TOOL_OVERRIDES`.
+#[derive(Debug, Clone, PartialEq)]
+enum Override {
+    Missing,
+    Version(Version),
+}
+
+/// Finds `tool` on the PATH and asks it for its version. Results are cached for the rest of the
+/// run; see [`forget_all`].
+///
+/// `PROJECTOR_TOOL_OVERRIDES` fakes results, for trying out code paths without uninstalling
+/// anything: a comma-separated list of `tool=missing` or `tool=VERSION`, where `*` stands for
+/// every tool, e.g. `cargo=missing,go=1.19.0`.
+pub fn probe(tool: &str) -> Option<ToolInfo> {
+    let mut cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(tool.to_string())
+        .or_insert_with(|| match override_for(tool) {
+            Some(Override::Missing) => None,
+            Some(Override::Version(version)) => Some(ToolInfo {
+                name: tool.to_string(),
+                path: which(tool).unwrap_or_else(|| PathBuf::from(tool)),
+                version: Some(version),
+            }),
+            None => run_probe(tool, None),
+        })
+        .clone()
+}
+
+/// [`probe`], with `tool` run from `dir` so version managers' shims pick the version pinned
+/// there. Not cached, since the answer depends on the directory. Falls back to the current
+/// directory if `dir` doesn't exist yet.
+pub fn probe_in(tool: &str, dir: &Path) -> Option<ToolInfo> {
+    if override_for(tool).is_some() || !dir.is_dir() {
+        return probe(tool);
+    }
+    run_probe(tool, Some(dir))
+}
+
+/// Whether `tool` is on the PATH and runs.
+pub fn is_installed(tool: &str) -> bool {
+    probe(tool).is_some()
+}
+
+/// Drops every cached result, e.g. after installing something.
+pub fn forget_all() {
+    cache().lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// A tool that's older than the minimum version the config asks for.
+#[derive(Debug, Clone)]
+pub struct TooOld {
+    pub tool: ToolInfo,
+    pub minimum: Version,
+}
+
+impl fmt::Display for TooOld {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} at {} is older than the minimum {} set in the config",
+            self.tool.name,
+            self.tool
+                .version
+                .as_ref()
+                .map(Version::to_string)
+                .unwrap_or_default(),
+            self.tool.path.display(),
+            self.minimum
+        )
+    }
+}
+
+/// The minimum versions the config sets for `lang`'s tools, from its `min_versions` table, e.g.
+/// `[min_versions.rust]` with `rustc = "1.80"`.
+pub fn minimum_versions(lang: ProgrammingLanguage) -> color_eyre::Result<Vec<(String, Version)>> {
+    let cfg: Config = confy::load("projector", None).unwrap_or_default();
+    minimums_in(&cfg, lang)
+}
+
+/// [`minimum_versions`] from an already loaded config.
+pub fn minimums_in(
+    cfg: &Config,
+    lang: ProgrammingLanguage,
+) -> color_eyre::Result<Vec<(String, Version)>> {
+    let mut minimums = Vec::new();
+    for (name, tools) in &cfg.min_versions {
+        if ProgrammingLanguage::from_name(name) != Some(lang) {
+            continue;
+        }
+        for (tool, version) in tools {
+            let minimum = parse_version(version).ok_or_else(|| {
+                eyre!(
+                    "Invalid minimum version '{}' for {} in the config",
+                    version,
+                    tool
+                )
+            })?;
+            minimums.push((tool.clone(), minimum));
+        }
+    }
+    Ok(minimums)
+}
+
+/// The installed tools for `lang` that are older than the minimums in the config. Missing tools
+/// and tools whose version can't be told aren't reported; scaffolders deal with those.
+pub fn outdated(lang: ProgrammingLanguage) -> color_eyre::Result<Vec<TooOld>> {
+    Ok(minimum_versions(lang)?
+        .into_iter()
+        .filter_map(|(tool, minimum)| too_old(probe(&tool)?, minimum))
+        .collect())
+}
+
+/// `tool` if its version is below `minimum`. A tool without a known version isn't.
+fn too_old(tool: ToolInfo, minimum: Version) -> Option<TooOld> {
+    let older = tool
+        .version
+        .as_ref()
+        .is_some_and(|version| *version < minimum);
+    older.then_some(TooOld { tool, minimum })
+}
+
+/// Parses a version as tools print it: `1.80`, `v22.9.0`, `go1.22.1` or `12.2.0-14`. Missing
+/// components count as zero and anything after the numbers is ignored.
+pub fn parse_version(text: &str) -> Option<Version> {
+    let text = text.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let numeric: String = text
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let mut parts = numeric
+        .trim_end_matches('.')
+        .split('.')
+        .map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some(Version::new(major, minor, patch))
+}
+
+fn cache() -> &'static Mutex<HashMap<String, Option<ToolInfo>>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Option<ToolInfo>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn override_for(tool: &str) -> Option<Override> {
+    parse_override(&env::var("PROJECTOR_TOOL_OVERRIDES").ok()?, tool)
+}
+
+/// `tool`'s entry in a `PROJECTOR_TOOL_OVERRIDES` value, see [`probe`].
+fn parse_override(overrides: &str, tool: &str) -> Option<Override> {
+    let entries: Vec<(&str, &str)> = overrides
+        .split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+    // A tool's own entry wins over `*`.
+    let (_, value) = entries
+        .iter()
+        .find(|(name, _)| *name == tool)
+        .or_else(|| entries.iter().find(|(name, _)| *name == "*"))?;
+    match *value {
+        "missing" => Some(Override::Missing),
+        version => parse_version(version).map(Override::Version),
+    }
+}
+
+fn run_probe(tool: &str, dir: Option<&Path>) -> Option<ToolInfo> {
+    let path = which(tool)?;
+    let mut command = Command::new(&path);
+    command.args(version_args(tool));
+    if let Some(dir) = dir {
+        // rustup would otherwise download a toolchain pinned there just to print its version.
+        command.current_dir(dir).env("RUSTUP_AUTO_INSTALL", "0");
+    }
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Java prints its version on stderr.
+    let text = [&output.stdout, &output.stderr]
+        .iter()
+        .filter_map(|stream| {
+            String::from_utf8_lossy(stream)
+                .lines()
+                .next()
+                .map(str::to_string)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(ToolInfo {
+        name: tool.to_string(),
+        path,
+        version: version_in(&text),
+    })
+}
+
+/// The version in a tool's `--version` output: the first word with a dot that parses, e.g.
+/// `1.85.0` in `rustc 1.85.0 (4d91de4e4 2025-02-17)`.
+fn version_in(output: &str) -> Option<Version> {
+    output
+        .split_whitespace()
+        .filter(|word| word.contains('.'))
+        .find_map(parse_version)
+}
+
+/// The arguments that make `tool` print its version.
+fn version_args(tool: &str) -> &'static [&'static str] {
+    let name = tool
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(tool)
+        .trim_end_matches(".exe");
+    match name {
+        "go" | "zig" => &["version"],
+        "java" | "javac" | "kotlinc" => &["-version"],
+        "lua" => &["-v"],
+        _ => &["--version"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(text: &str) -> Version {
+        Version::parse(text).unwrap()
+    }
+
+    fn tool(version: Option<&str>) -> ToolInfo {
+        ToolInfo {
+            name: "rustc".to_string(),
+            path: PathBuf::from("/usr/bin/rustc"),
+            version: version.map(self::version),
+        }
+    }
+
+    #[test]
+    fn parse_version_pads_and_skips_prefixes() {
+        assert_eq!(parse_version("go1.22.1"), Some(version("1.22.1")));
+        assert_eq!(parse_version("v22.9.0"), Some(version("22.9.0")));
+        assert_eq!(parse_version("1.80"), Some(version("1.80.0")));
+        assert_eq!(parse_version("12.2.0-14"), Some(version("12.2.0")));
+        assert_eq!(parse_version("\"21.0.4\""), Some(version("21.0.4")));
+        assert_eq!(parse_version("stable"), None);
+    }
+
+    #[test]
+    fn version_in_finds_the_version_in_tool_output() {
+        assert_eq!(
+            version_in("go version go1.22.1 linux/amd64"),
+            Some(version("1.22.1"))
+        );
+        assert_eq!(version_in("Python 3.12.0"), Some(version("3.12.0")));
+        assert_eq!(
+            version_in("rustc 1.85.0 (4d91de4e4 2025-02-17)"),
+            Some(version("1.85.0"))
+        );
+        assert_eq!(
+            version_in("openjdk version \"21.0.4\" 2024-07-16"),
+            Some(version("21.0.4"))
+        );
+        assert_eq!(version_in("make: no version here"), None);
+    }
+
+    #[test]
+    fn version_args_match_each_tool() {
+        let cases: [(&str, &[&str]); 9] = [
+            ("go", &["version"]),
+            ("zig", &["version"]),
+            ("/opt/zig/zig", &["version"]),
+            ("java", &["-version"]),
+            ("C:\\jdk\\bin\\javac.exe", &["-version"]),
+            ("kotlinc", &["-version"]),
+            ("lua", &["-v"]),
+            ("rustc", &["--version"]),
+            ("node.exe", &["--version"]),
+        ];
+        for (tool, args) in cases {
+            assert_eq!(version_args(tool), args, "{}", tool);
+        }
+        assert_eq!(version_in("0.13.0\n"), Some(version("0.13.0")));
+        assert_eq!(
+            version_in("Lua 5.4.6  Copyright (C) 1994-2023 Lua.org, PUC-Rio"),
+            Some(version("5.4.6"))
+        );
+    }
+
+    #[test]
+    fn too_old_compares_against_the_minimum() {
+        let minimum = parse_version("1.80").unwrap();
+        assert!(too_old(tool(Some("1.79.9")), minimum.clone()).is_some());
+        assert!(too_old(tool(Some("1.80.0")), minimum.clone()).is_none());
+        assert!(too_old(tool(Some("1.85.0")), minimum.clone()).is_none());
+        // An unknown version isn't reported as too old.
+        assert!(too_old(tool(None), minimum).is_none());
+    }
+
+    #[test]
+    fn parse_override_prefers_the_tool_over_the_wildcard() {
+        let overrides = "cargo=missing, go = 1.19 ,*=2.0.0,broken";
+        assert_eq!(parse_override(overrides, "cargo"), Some(Override::Missing));
+        assert_eq!(
+            parse_override(overrides, "go"),
+            Some(Override::Version(version("1.19.0")))
+        );
+        assert_eq!(
+            parse_override(overrides, "node"),
+            Some(Override::Version(version("2.0.0")))
+        );
+        assert_eq!(parse_override("go=latest", "go"), None);
+        assert_eq!(parse_override("", "go"), None);
+    }
+}
@@ -1,102 +1,127 @@
-use std::env;
-use std::fs;
-use std::path::Path;
-use std::process::Command;
+use color_eyre::eyre::eyre;
 
-use crate::database::model::Project;
+use crate::install::{self, plan::InstallPlan, Tool};
+use crate::probe;
+use crate::scaffold::{self, plan::Plan, ScaffoldOptions};
 use crate::state::languages::ProgrammingLanguage;
-use crate::ui::{grab_string::ask, yn};
-use crate::util::is_tool_installed;
+use crate::template;
+use crate::ui::prompt::Prompter;
 
-pub fn setup(project_name: Option<&str>) {
-    if !is_tool_installed("gcc") || !is_tool_installed("make") {
-        let choice = yn::ask("GCC or Make is not installed. Would you like to install them? (y/n)")
-            .expect("Failed to get user input");
+const BUILD_SYSTEMS: [&str; 3] = ["cmake", "meson", "make"];
 
-        if choice {
-            if cfg!(target_os = "windows") {
-                let install_choice = ask("Would you like to install Visual Studio or manually set up MinGW/Cygwin? (vs/mingw)")
-                    .expect("Failed to get user input");
+/// Scaffolds a C or C++ project with src/, include/ and tests/ and a hello-world that builds
+/// out of the box. Every build system leaves a compile_commands.json for clangd.
+pub fn setup(
+    lang: ProgrammingLanguage,
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let (lang_name, compilers) = match lang {
+        ProgrammingLanguage::CPlusPlus => ("cpp", ["g++", "c++"]),
+        _ => ("c", ["gcc", "cc"]),
+    };
+    let build_system = opts.choose(
+        prompter,
+        opts.build_system.as_deref(),
+        "Which build system should the project use?",
+        &BUILD_SYSTEMS,
+    )?;
 
-                if install_choice.trim().eq_ignore_ascii_case("vs") {
-                    let temp_dir = env::temp_dir().join("vs_install");
-                    if temp_dir.exists() {
-                        fs::remove_dir_all(&temp_dir)
-                            .expect("Failed to remove existing temp directory");
-                    }
-                    fs::create_dir_all(&temp_dir).expect("Failed to create temp directory");
+    if !compilers
+        .iter()
+        .any(|compiler| probe::is_installed(compiler))
+    {
+        install_toolchain(opts, prompter)?;
+        return Ok(());
+    }
+    let missing: Vec<&str> = match build_system {
+        "cmake" => vec!["cmake"],
+        "meson" => vec!["meson", "ninja"],
+        _ => vec!["make"],
+    }
+    .into_iter()
+    .filter(|tool| !probe::is_installed(tool))
+    .collect();
+    if !missing.is_empty() {
+        println!(
+            "{} is not installed. Install it with your package manager, or pick another build system with --build-system, then run projector again.",
+            missing.join(" and ")
+        );
+        return Ok(());
+    }
 
-                    let vs_installer_path = temp_dir.join("vs_installer.exe");
-                    Command::new("curl")
-                        .args(&[
-                            "-o",
-                            vs_installer_path.to_str().unwrap(),
-                            "https://c2rsetup.officeapps.live.com/c2r/downloadVS.aspx?sku=community&channel=Release&version=VS2022&source=VSLandingPage&cid=2030",
-                        ])
-                        .status()
-                        .expect("Failed to download Visual Studio installer");
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter.input("Project name? (leave blank to use current directory)")?,
+    };
+    let target = scaffold::target(lang, &name, opts, prompter)?;
 
-                    Command::new(vs_installer_path)
-                        .status()
-                        .expect("Failed to execute Visual Studio installer");
+    let template_name = format!("{}-{}", lang_name, build_system);
+    let template = template::find(&template_name)?
+        .ok_or_else(|| eyre!("No {} template found", template_name))?;
+    let vars = template::builtin_variables(&target.dir);
+    let vars = template.resolve_variables(vars, opts, prompter)?;
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
 
-                    fs::remove_dir_all(&temp_dir).expect("Failed to remove temp directory");
-                } else {
-                    println!("Please manually set up MinGW or Cygwin for GCC and Make.");
-                }
-            } else {
-                Command::new("sh")
-                    .arg("-c")
-                    .arg("sudo apt-get update && sudo apt-get install -y build-essential")
-                    .status()
-                    .expect("Failed to execute command to install GCC and Make");
-            }
-            println!("Note: You will have to run the projector setup again due to new environment files. Please restart your terminal.");
-        } else {
-            println!("Exiting as GCC or Make is not installed.");
+    let mut plan = Plan::new(&target);
+    plan.render(template, vars)?;
+    // Configuring up front writes build/compile_commands.json; the Make template ships its own.
+    // A failure is only reported, since it can be rerun by hand.
+    match build_system {
+        "cmake" => {
+            plan.optional_command("cmake", &["-S", ".", "-B", "build"]);
+        }
+        "meson" => {
+            plan.optional_command("meson", &["setup", "build"]);
         }
-        return;
+        _ => {}
     }
+    if git {
+        plan.command("git", &["init"]);
+    }
+    plan.execute(lang, opts, prompter)
+}
 
-    match project_name {
-        Some(name) => {
-            let path = Path::new(name)
-                .canonicalize()
-                .expect("Failed to get full path");
-            fs::create_dir(name).expect("Failed to create project directory");
-            println!("Project {} created successfully", name);
-            let mut project =
-                Project::new(ProgrammingLanguage::C, name.to_string(), path.as_path());
-            crate::post_setup::run_post_setup(&mut project).expect("Failed to run post setup");
+fn install_toolchain(
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    println!("No C/C++ compiler is installed.");
+    let plan = if cfg!(target_os = "windows") {
+        let install_choice = if opts.assume_yes {
+            "vs".to_string()
+        } else {
+            prompter.input(
+                "Would you like to install Visual Studio or manually set up MinGW/Cygwin? (vs/mingw)",
+            )?
+        };
+        if !install_choice.trim().eq_ignore_ascii_case("vs") {
+            println!("Please manually set up MinGW or Cygwin for GCC and Make.");
+            return Ok(());
         }
-        None => {
-            let answer = ask("Project name? (leave blank to use current directory)")
-                .expect("Failed to get project name");
+        let mut plan = InstallPlan::new("visual-studio", "Visual Studio");
+        // Microsoft publishes no checksum for the bootstrapper, which verifies what it fetches.
+        let installer = plan.download(
+            "https://c2rsetup.officeapps.live.com/c2r/downloadVS.aspx?sku=community&channel=Release&version=VS2022&source=VSLandingPage&cid=2030",
+            "vs_installer.exe",
+        )?;
+        plan.run(&[&installer.to_string_lossy()], true);
+        plan
+    } else {
+        InstallPlan::packages(&[Tool::Gcc, Tool::Make])?
+    };
 
-            if answer.trim().is_empty() {
-                let path = Path::new(".")
-                    .canonicalize()
-                    .expect("Failed to get full path");
-                println!("Initialized current directory as a C project");
-                let mut project = Project::new(
-                    ProgrammingLanguage::C,
-                    path.file_name().unwrap().to_str().unwrap().to_string(),
-                    path.as_path(),
-                );
-                crate::post_setup::run_post_setup(&mut project).expect("Failed to run post setup");
-            } else {
-                let path = Path::new(answer.trim())
-                    .canonicalize()
-                    .expect("Failed to get full path");
-                fs::create_dir(answer.trim()).expect("Failed to create project directory");
-                println!("Project {} created successfully", answer.trim());
-                let mut project = Project::new(
-                    ProgrammingLanguage::C,
-                    path.file_name().unwrap().to_str().unwrap().to_string(),
-                    path.as_path(),
-                );
-                crate::post_setup::run_post_setup(&mut project).expect("Failed to run post setup");
-            }
-        }
+    if install::confirm_and_run(&plan, opts, prompter)? {
+        println!("Note: You will have to run the projector setup again due to new environment files. Please restart your terminal.");
+    } else if !opts.dry_run {
+        println!("Exiting as no C/C++ compiler is installed.");
     }
+    Ok(())
 }
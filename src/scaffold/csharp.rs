@@ -0,0 +1,98 @@
+use color_eyre::eyre::eyre;
+
+use crate::probe;
+use crate::scaffold::{self, plan::Plan, ScaffoldOptions};
+use crate::state::languages::ProgrammingLanguage;
+use crate::ui::prompt::Prompter;
+
+/// `dotnet new` template short names offered for the main project.
+const KINDS: [&str; 4] = ["console", "classlib", "webapi", "xunit"];
+
+/// Scaffolds a .NET project with `dotnet new`.
+///
+/// Without a solution the project sits at the root of the directory. With one, the project goes
+/// in `src/<name>/` and an xunit project referencing it in `tests/<name>.Tests/`, both added to
+/// `<name>.sln` at the root.
+pub fn setup(
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    if !probe::is_installed("dotnet") {
+        println!("The .NET SDK is not installed. Install it from https://dotnet.microsoft.com/download or your package manager, then run projector again.");
+        return Ok(());
+    }
+
+    let kind = opts.choose(
+        prompter,
+        opts.kind.as_deref(),
+        "What kind of project is this?",
+        &KINDS,
+    )?;
+
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter.input("Project name? (leave blank to use current directory)")?,
+    };
+    let target = scaffold::target(ProgrammingLanguage::CSharp, &name, opts, prompter)?;
+    let dir_name = target
+        .dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| eyre!("Failed to get project name"))?;
+
+    // A test project only makes sense next to something it can test.
+    let solution = opts.confirm(
+        prompter,
+        opts.solution,
+        "Create a solution with a test project? (y/n)",
+        kind != "xunit",
+    )?;
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
+    let gitignore = git
+        && opts.confirm(
+            prompter,
+            opts.gitignore,
+            "Add a .gitignore file? (y/n)",
+            true,
+        )?;
+
+    let mut plan = Plan::new(&target);
+    if solution {
+        let main_dir = format!("src/{}", dir_name);
+        plan.command("dotnet", &["new", "sln", "--name", &dir_name])
+            .command(
+                "dotnet",
+                &["new", kind, "--name", &dir_name, "--output", &main_dir],
+            )
+            .command("dotnet", &["sln", "add", &main_dir]);
+        if kind != "xunit" {
+            let test_name = format!("{}.Tests", dir_name);
+            let test_dir = format!("tests/{}", test_name);
+            plan.command(
+                "dotnet",
+                &["new", "xunit", "--name", &test_name, "--output", &test_dir],
+            )
+            .command("dotnet", &["sln", "add", &test_dir])
+            .command("dotnet", &["add", &test_dir, "reference", &main_dir]);
+        }
+    } else {
+        plan.command(
+            "dotnet",
+            &["new", kind, "--name", &dir_name, "--output", "."],
+        );
+    }
+    if git {
+        plan.command("git", &["init"]);
+    }
+    if gitignore {
+        plan.command("dotnet", &["new", "gitignore"]);
+    }
+    plan.execute(ProgrammingLanguage::CSharp, opts, prompter)
+}
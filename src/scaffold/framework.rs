@@ -0,0 +1,108 @@
+use color_eyre::eyre::eyre;
+
+use crate::scaffold::{self, javascript, plan::Plan, ScaffoldOptions};
+use crate::state::languages::ProgrammingLanguage;
+use crate::template::{self, Template};
+use crate::ui::prompt::Prompter;
+
+/// Option for scaffolding the plain language instead of a framework.
+const NO_FRAMEWORK: &str = "none";
+
+/// Picks a framework for `lang` from `--framework` or by asking. Returns `None` for a plain
+/// project, including when the language has no frameworks.
+pub fn choose(
+    lang: ProgrammingLanguage,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<Option<Template>> {
+    let mut frameworks = template::frameworks(lang)?;
+    // `--framework` matches the template name or the framework's display name.
+    let preset = match opts.framework.as_deref() {
+        Some(name) if name.eq_ignore_ascii_case(NO_FRAMEWORK) => return Ok(None),
+        Some(name) => Some(
+            frameworks
+                .iter()
+                .find(|t| {
+                    t.manifest.name.eq_ignore_ascii_case(name)
+                        || framework_name(t).eq_ignore_ascii_case(name)
+                })
+                .map(framework_name)
+                .ok_or_else(|| eyre!("No framework '{}' for {:?}", name, lang))?,
+        ),
+        None if frameworks.is_empty() => return Ok(None),
+        None => None,
+    };
+
+    let mut options = vec![NO_FRAMEWORK];
+    options.extend(frameworks.iter().map(framework_name));
+    let choice = opts
+        .choose(
+            prompter,
+            preset,
+            "Which framework should the project use?",
+            &options,
+        )?
+        .to_string();
+    let index = frameworks.iter().position(|t| framework_name(t) == choice);
+    Ok(index.map(|index| frameworks.swap_remove(index)))
+}
+
+/// The language of the framework called `name`, for `projector new --framework NAME`.
+pub fn language(name: &str) -> color_eyre::Result<ProgrammingLanguage> {
+    template::all()?
+        .iter()
+        .filter(|t| t.manifest.framework.is_some())
+        .find(|t| {
+            t.manifest.name.eq_ignore_ascii_case(name)
+                || framework_name(t).eq_ignore_ascii_case(name)
+        })
+        .and_then(|t| t.manifest.language.as_deref())
+        .and_then(ProgrammingLanguage::from_name)
+        .ok_or_else(|| eyre!("Unknown framework '{}'; see `projector templates`", name))
+}
+
+fn framework_name(template: &Template) -> &str {
+    template
+        .manifest
+        .framework
+        .as_deref()
+        .unwrap_or(&template.manifest.name)
+}
+
+/// Scaffolds a project from a framework template, including the template's steps.
+pub fn setup(
+    template: Template,
+    lang: ProgrammingLanguage,
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter.input("Project name? (leave blank to use current directory)")?,
+    };
+    let target = scaffold::target(lang, &name, opts, prompter)?;
+
+    let mut vars = template::builtin_variables(&target.dir);
+    if lang == ProgrammingLanguage::JavaScript {
+        let Some(package_manager) = javascript::package_manager(opts, prompter)? else {
+            return Ok(());
+        };
+        vars.insert("package_manager".to_string(), package_manager);
+    }
+    let vars = template.resolve_variables(vars, opts, prompter)?;
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
+
+    let mut plan = Plan::new(&target);
+    plan.render(template, vars)?;
+    if git {
+        plan.command("git", &["init"]);
+    }
+    plan.execute(lang, opts, prompter)
+}
@@ -0,0 +1,66 @@
+use color_eyre::eyre::{bail, eyre};
+
+use crate::pins;
+use crate::scaffold::{self, javascript, plan::Plan, ScaffoldOptions};
+use crate::state::languages::ProgrammingLanguage;
+use crate::template::{self, fetch};
+use crate::ui::prompt::Prompter;
+
+/// Scaffolds a project from a template given as a path, git URL or template name.
+///
+/// `lang` overrides the language declared in the template's manifest; one of the two is needed
+/// so the project can be registered in the database.
+pub fn setup(
+    spec: &str,
+    lang: Option<ProgrammingLanguage>,
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let template = fetch::load(spec)?;
+    let lang = match lang {
+        Some(lang) => lang,
+        None => template
+            .manifest
+            .language
+            .as_deref()
+            .and_then(ProgrammingLanguage::from_name)
+            .ok_or_else(|| {
+                eyre!(
+                    "Template '{}' doesn't declare a known language; pass one, e.g. `projector new rust <name> --template {}`",
+                    template.manifest.name,
+                    spec
+                )
+            })?,
+    };
+
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => bail!("A project name is required when running with --yes"),
+        None => prompter.input("Project name?")?,
+    };
+    if name.is_empty() {
+        bail!("Exiting as no project name was provided.");
+    }
+
+    let target = scaffold::target(lang, &name, opts, prompter)?;
+    pins::check(&target.dir, Some(lang), opts, prompter)?;
+
+    prompter.suspend();
+    println!(
+        "Rendering template '{}' into {}",
+        template.manifest.name,
+        target.dir.display()
+    );
+    let mut vars = template::builtin_variables(&target.dir);
+    if lang == ProgrammingLanguage::JavaScript {
+        let Some(package_manager) = javascript::package_manager(opts, prompter)? else {
+            return Ok(());
+        };
+        vars.insert("package_manager".to_string(), package_manager);
+    }
+    let vars = template.resolve_variables(vars, opts, prompter)?;
+    let mut plan = Plan::new(&target);
+    plan.render(template, vars)?;
+    plan.execute(lang, opts, prompter)
+}
@@ -0,0 +1,71 @@
+use color_eyre::eyre::eyre;
+
+use crate::install::{self, Tool};
+use crate::probe;
+use crate::scaffold::{self, naming, plan::Plan, ScaffoldOptions};
+use crate::state::languages::ProgrammingLanguage;
+use crate::template;
+use crate::ui::prompt::Prompter;
+
+/// `single` is one `main.go`; `cmd` splits entry points into cmd/ and packages into internal/.
+const LAYOUTS: [&str; 2] = ["single", "cmd"];
+
+pub fn setup(
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    if !probe::is_installed("go") && !install::offer(Tool::Go, opts, prompter)? {
+        println!(
+            "Install Go from https://go.dev/dl or your package manager, then run projector again."
+        );
+        return Ok(());
+    }
+
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter.input("Project name? (leave blank to use current directory)")?,
+    };
+    let target = scaffold::target(ProgrammingLanguage::Go, &name, opts, prompter)?;
+
+    let layout = opts.choose(
+        prompter,
+        opts.layout.as_deref(),
+        "Which layout should the project use?",
+        &LAYOUTS,
+    )?;
+    let template_name = match layout {
+        "cmd" => "go-cmd",
+        _ => "go",
+    };
+    let template = template::find(template_name)?
+        .ok_or_else(|| eyre!("No {} template found", template_name))?;
+    let vars = template::builtin_variables(&target.dir);
+    let vars = template.resolve_variables(vars, opts, prompter)?;
+    let module_path = vars
+        .get("module_path")
+        .cloned()
+        .ok_or_else(|| eyre!("Template {} doesn't define module_path", template_name))?;
+    naming::check_module_path(&module_path)
+        .map_err(|e| eyre!("'{}' can't be used as a module path: {}", module_path, e))?;
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
+
+    let mut plan = Plan::new(&target);
+    // When merging, an existing go.mod is kept like any other file.
+    if !target.dir.join("go.mod").exists() {
+        plan.command("go", &["mod", "init", &module_path]);
+    }
+    plan.render(template, vars)?
+        // Templates are written gofmt-clean, but user overrides might not be.
+        .optional_command("gofmt", &["-w", "."]);
+    if git {
+        plan.command("git", &["init"]);
+    }
+    plan.execute(ProgrammingLanguage::Go, opts, prompter)
+}
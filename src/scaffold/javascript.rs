@@ -0,0 +1,103 @@
+use color_eyre::eyre::eyre;
+
+use crate::install::{self, Tool};
+use crate::probe;
+use crate::scaffold::{self, plan::Plan, ScaffoldOptions};
+use crate::state::languages::ProgrammingLanguage;
+use crate::template;
+use crate::ui::prompt::Prompter;
+
+const PACKAGE_MANAGERS: [&str; 4] = ["npm", "pnpm", "yarn", "bun"];
+
+fn installed_package_managers() -> Vec<&'static str> {
+    PACKAGE_MANAGERS
+        .into_iter()
+        .filter(|manager| probe::is_installed(manager))
+        .collect()
+}
+
+/// Picks the package manager for a JavaScript project from `--package-manager` or by asking,
+/// offering to install Node.js if none is found. `None` means there still isn't one.
+pub fn package_manager(
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<Option<String>> {
+    let mut available = installed_package_managers();
+    if available.is_empty() && install::offer(Tool::Node, opts, prompter)? {
+        available = installed_package_managers();
+    }
+    if available.is_empty() {
+        println!("No JavaScript package manager found (npm, pnpm, yarn or bun).");
+        println!("Install Node.js from https://nodejs.org or your package manager, then run projector again.");
+        return Ok(None);
+    }
+    let package_manager = opts.choose(
+        prompter,
+        opts.package_manager.as_deref(),
+        "Which package manager should the project use?",
+        &available,
+    )?;
+    Ok(Some(package_manager.to_string()))
+}
+
+pub fn setup(
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let Some(package_manager) = package_manager(opts, prompter)? else {
+        return Ok(());
+    };
+    let package_manager = package_manager.as_str();
+
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter.input("Project name? (leave blank to use current directory)")?,
+    };
+    let target = scaffold::target(ProgrammingLanguage::JavaScript, &name, opts, prompter)?;
+
+    let typescript = opts.confirm(prompter, opts.typescript, "Use TypeScript? (y/n)", false)?;
+    let esm = opts.confirm(
+        prompter,
+        opts.esm,
+        "Use ES modules (import/export) instead of CommonJS (require)? (y/n)",
+        true,
+    )?;
+
+    let template_name = if typescript { "typescript" } else { "node" };
+    let template = template::find(template_name)?
+        .ok_or_else(|| eyre!("No {} template found", template_name))?;
+    let mut vars = template::builtin_variables(&target.dir);
+    vars.insert(
+        "module_type".to_string(),
+        if esm { "module" } else { "commonjs" }.to_string(),
+    );
+    let vars = template.resolve_variables(vars, opts, prompter)?;
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
+
+    let mut plan = Plan::new(&target);
+    plan.render(template, vars)?;
+    if git {
+        plan.command("git", &["init"]);
+    }
+    // A failed install doesn't undo the scaffold, since it's easy to rerun.
+    plan.optional_command(package_manager, &["install"]);
+    if typescript {
+        let add = if package_manager == "npm" {
+            ["install", "--save-dev"]
+        } else {
+            ["add", "-D"]
+        };
+        plan.optional_command(
+            package_manager,
+            &[add[0], add[1], "typescript", "@types/node"],
+        );
+    }
+    plan.execute(ProgrammingLanguage::JavaScript, opts, prompter)
+}
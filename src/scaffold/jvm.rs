@@ -0,0 +1,72 @@
+use color_eyre::eyre::eyre;
+
+use crate::probe;
+use crate::scaffold::{self, naming, plan::Plan, ScaffoldOptions};
+use crate::state::languages::ProgrammingLanguage;
+use crate::template;
+use crate::ui::prompt::Prompter;
+
+const BUILD_SYSTEMS: [&str; 2] = ["maven", "gradle"];
+
+/// Scaffolds a Java or Kotlin project with the standard Maven/Gradle source layout.
+pub fn setup(
+    lang: ProgrammingLanguage,
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let lang_name = match lang {
+        ProgrammingLanguage::Kotlin => "kotlin",
+        _ => "java",
+    };
+    if !probe::is_installed("java") {
+        println!("No JDK found. Install one (e.g. Temurin from https://adoptium.net or your package manager), then run projector again.");
+        return Ok(());
+    }
+
+    let build_system = opts.choose(
+        prompter,
+        opts.build_system.as_deref(),
+        "Which build tool should the project use?",
+        &BUILD_SYSTEMS,
+    )?;
+
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter.input("Project name? (leave blank to use current directory)")?,
+    };
+    let target = scaffold::target(lang, &name, opts, prompter)?;
+
+    let template_name = format!("{}-{}", lang_name, build_system);
+    let template = template::find(&template_name)?
+        .ok_or_else(|| eyre!("No {} template found", template_name))?;
+    let vars = template::builtin_variables(&target.dir);
+    let vars = template.resolve_variables(vars, opts, prompter)?;
+    if let Some(package) = vars.get("package") {
+        naming::check_package(lang, package)
+            .map_err(|e| eyre!("'{}' can't be used as a package name: {}", package, e))?;
+    }
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
+
+    let mut plan = Plan::new(&target);
+    plan.render(template, vars)?;
+    // `gradlew` lets the project build without a system-wide Gradle, but only Gradle itself
+    // can generate it.
+    if build_system == "gradle" {
+        if probe::is_installed("gradle") {
+            plan.optional_command("gradle", &["wrapper", "--quiet"]);
+        } else {
+            println!("Gradle is not installed, skipping the Gradle wrapper. Run `gradle wrapper` in the project once it is.");
+        }
+    }
+    if git {
+        plan.command("git", &["init"]);
+    }
+    plan.execute(lang, opts, prompter)
+}
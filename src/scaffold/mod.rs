@@ -1,3 +1,416 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{bail, eyre};
+
+use crate::pins;
+use crate::probe;
+use crate::state::languages::ProgrammingLanguage;
+use crate::ui::prompt::Prompter;
+
 pub mod c;
+pub mod csharp;
+pub mod framework;
+pub mod from_template;
+pub mod go;
+pub mod javascript;
+pub mod jvm;
+pub mod naming;
+pub mod plan;
 pub mod python;
 pub mod rust;
+pub mod swift;
+
+/// Answers to scaffolding questions supplied up front, e.g. from command line flags.
+///
+/// Any question without an answer is asked interactively, unless `assume_yes` is set, in which
+/// case its default is used without prompting.
+#[derive(Debug, Default, Clone)]
+pub struct ScaffoldOptions {
+    pub venv: Option<bool>,
+    pub requirements: Option<bool>,
+    pub git: Option<bool>,
+    pub gitignore: Option<bool>,
+    /// JavaScript package manager or Python packaging tool, e.g. `pnpm` or `uv`.
+    pub package_manager: Option<String>,
+    /// Whether a JavaScript project uses TypeScript.
+    pub typescript: Option<bool>,
+    /// Whether a JavaScript project uses ES modules rather than CommonJS.
+    pub esm: Option<bool>,
+    /// Build system for languages that offer more than one, e.g. `gradle`.
+    pub build_system: Option<String>,
+    /// Project layout for languages that offer more than one, e.g. `cmd` for Go.
+    pub layout: Option<String>,
+    /// Kind of project for languages whose tooling offers several, e.g. `workspace` for Rust or
+    /// `classlib` for C#.
+    pub kind: Option<String>,
+    /// Whether a C# project gets a solution file and a test project.
+    pub solution: Option<bool>,
+    /// Rust edition, e.g. `2021`.
+    pub edition: Option<String>,
+    /// Starter preset for languages that offer them, e.g. `cli` for Rust.
+    pub preset: Option<String>,
+    /// Member crates of a Rust workspace, as `name`, `name:bin` or `name:lib`.
+    pub members: Vec<String>,
+    /// Whether a Rust project gets rust-toolchain.toml, rustfmt.toml and clippy.toml.
+    pub toolchain_files: Option<bool>,
+    /// Framework template to scaffold instead of a plain project, e.g. `fastapi`, or `none`.
+    pub framework: Option<String>,
+    /// What to do when the project directory exists and isn't empty: `refuse` or `merge`.
+    pub if_exists: Option<String>,
+    /// Template variables given as `--var name=value`.
+    pub variables: Vec<(String, String)>,
+    pub assume_yes: bool,
+    /// Print the scaffolding plan instead of running it.
+    pub dry_run: bool,
+}
+
+impl ScaffoldOptions {
+    /// Returns the value given for a template variable on the command line, if any.
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Resolves a yes/no question from a preset answer, the default, or by asking the user.
+    pub fn confirm(
+        &self,
+        prompter: &mut dyn Prompter,
+        preset: Option<bool>,
+        question: &str,
+        default: bool,
+    ) -> color_eyre::Result<bool> {
+        match preset {
+            Some(answer) => Ok(answer),
+            None if self.assume_yes => Ok(default),
+            None => prompter.confirm(question),
+        }
+    }
+
+    /// Resolves a multiple-choice question from a preset answer, the first option, or by
+    /// asking the user. A preset must match one of `options`.
+    pub fn choose<'a>(
+        &self,
+        prompter: &mut dyn Prompter,
+        preset: Option<&str>,
+        question: &str,
+        options: &[&'a str],
+    ) -> color_eyre::Result<&'a str> {
+        if let Some(preset) = preset {
+            return options
+                .iter()
+                .find(|option| option.eq_ignore_ascii_case(preset))
+                .copied()
+                .ok_or_else(|| eyre!("'{}' is not one of: {}", preset, options.join(", ")));
+        }
+        if self.assume_yes || options.len() == 1 {
+            return options
+                .first()
+                .copied()
+                .ok_or_else(|| eyre!("Nothing to choose from for: {}", question));
+        }
+        Ok(options[prompter.select(question, options)?])
+    }
+}
+
+/// Answers to "the project directory isn't empty", refusing first so it's the default.
+const IF_EXISTS: [&str; 2] = ["refuse", "merge"];
+
+/// Where a project gets scaffolded.
+pub struct Target {
+    /// Absolute path of the project directory, which may not exist yet.
+    pub dir: PathBuf,
+    /// Whether the directory already has files that scaffolding should keep.
+    pub merge: bool,
+}
+
+/// Resolves where a project named `name` goes, see [`project_dir`].
+///
+/// The directory name has to be valid for `lang`; if it isn't, the user is offered a similar
+/// name that is. A directory that exists and isn't empty is refused unless the user chooses to
+/// merge into it, either when asked or with `--if-exists merge`.
+pub fn target(
+    lang: ProgrammingLanguage,
+    name: &str,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<Target> {
+    let mut dir = project_dir(name)?;
+    let dir_name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| eyre!("Failed to get project name"))?;
+    if let Err(invalid) = naming::check_project_name(lang, &dir_name) {
+        // The current directory can't be renamed from here, so there's nothing to offer.
+        let in_cwd = name.is_empty() || name == ".";
+        match invalid.suggestion {
+            Some(suggestion)
+                if !in_cwd
+                    && !opts.assume_yes
+                    && prompter.confirm(&format!(
+                        "'{}' can't be used as a {:?} project name: {}. Use '{}' instead? (y/n)",
+                        dir_name, lang, invalid.reason, suggestion
+                    ))? =>
+            {
+                dir.set_file_name(suggestion);
+            }
+            _ => bail!(
+                "'{}' can't be used as a {:?} project name: {}",
+                dir_name,
+                lang,
+                invalid
+            ),
+        }
+    }
+
+    let merge = is_non_empty_dir(&dir)?
+        && match opts.choose(
+            prompter,
+            opts.if_exists.as_deref(),
+            &format!(
+                "{} already exists and isn't empty. Refuse, or merge the project into it keeping existing files?",
+                dir.display()
+            ),
+            &IF_EXISTS,
+        )? {
+            "merge" => true,
+            _ => bail!(
+                "Directory {} already exists and isn't empty. Pass --if-exists merge to scaffold into it, keeping the files already there.",
+                dir.display()
+            ),
+        };
+    Ok(Target { dir, merge })
+}
+
+fn is_non_empty_dir(path: &Path) -> color_eyre::Result<bool> {
+    Ok(path.is_dir() && fs::read_dir(path)?.next().is_some())
+}
+
+/// Where a project named `name` goes: the current directory when the name is blank or `.`,
+/// otherwise a directory of that name in it. The directory doesn't have to exist yet.
+fn project_dir(name: &str) -> color_eyre::Result<PathBuf> {
+    let cwd = env::current_dir()?;
+    if name.is_empty() || name == "." {
+        return Ok(cwd);
+    }
+    let path = cwd.join(name);
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(file_name)) if parent.exists() => {
+            Ok(parent.canonicalize()?.join(file_name))
+        }
+        _ => Ok(path),
+    }
+}
+
+/// Runs the scaffolder for `lang`, or for one of its frameworks if the user picks one.
+pub fn setup(
+    lang: ProgrammingLanguage,
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let outdated = probe::outdated(lang)?;
+    if !outdated.is_empty() {
+        let tools = outdated
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        let it = if outdated.len() == 1 { "it" } else { "them" };
+        bail!("{}. Update {}, then run projector again.", tools, it);
+    }
+    let dir = match project_name {
+        Some(name) => project_dir(name)?,
+        None => env::current_dir()?,
+    };
+    pins::check(&dir, Some(lang), opts, prompter)?;
+    if let Some(template) = framework::choose(lang, opts, prompter)? {
+        return framework::setup(template, lang, project_name, opts, prompter);
+    }
+    match lang {
+        ProgrammingLanguage::Rust => rust::setup(project_name, opts, prompter),
+        ProgrammingLanguage::C | ProgrammingLanguage::CPlusPlus => {
+            c::setup(lang, project_name, opts, prompter)
+        }
+        ProgrammingLanguage::Python => python::setup(project_name, opts, prompter),
+        ProgrammingLanguage::JavaScript => javascript::setup(project_name, opts, prompter),
+        ProgrammingLanguage::CSharp => csharp::setup(project_name, opts, prompter),
+        ProgrammingLanguage::Go => go::setup(project_name, opts, prompter),
+        ProgrammingLanguage::Java | ProgrammingLanguage::Kotlin => {
+            jvm::setup(lang, project_name, opts, prompter)
+        }
+        ProgrammingLanguage::Swift => swift::setup(project_name, opts, prompter),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::ui::prompt::ScriptedPrompter;
+
+    fn no_answers() -> ScriptedPrompter {
+        ScriptedPrompter::new(Vec::<String>::new())
+    }
+
+    #[test]
+    fn confirm_prefers_preset_then_default_then_prompt() {
+        let mut prompter = no_answers();
+        let opts = ScaffoldOptions::default();
+        assert!(!opts
+            .confirm(&mut prompter, Some(false), "Git?", true)
+            .unwrap());
+
+        let yes = ScaffoldOptions {
+            assume_yes: true,
+            ..Default::default()
+        };
+        assert!(yes.confirm(&mut prompter, None, "Git?", true).unwrap());
+        assert!(!yes.confirm(&mut prompter, None, "Git?", false).unwrap());
+
+        let mut prompter = ScriptedPrompter::new(["n"]);
+        assert!(!opts.confirm(&mut prompter, None, "Git?", true).unwrap());
+        assert!(opts.confirm(&mut prompter, None, "Git?", true).is_err());
+    }
+
+    #[test]
+    fn choose_matches_presets_case_insensitively() {
+        let opts = ScaffoldOptions::default();
+        let options = ["npm", "pnpm", "yarn"];
+        let chosen = opts
+            .choose(&mut no_answers(), Some("PNPM"), "Manager?", &options)
+            .unwrap();
+        assert_eq!(chosen, "pnpm");
+    }
+
+    #[test]
+    fn choose_rejects_an_invalid_preset() {
+        let opts = ScaffoldOptions::default();
+        let err = opts
+            .choose(&mut no_answers(), Some("pip"), "Manager?", &["npm", "pnpm"])
+            .unwrap_err();
+        assert!(err.to_string().contains("'pip' is not one of: npm, pnpm"));
+    }
+
+    #[test]
+    fn choose_defaults_to_the_first_option() {
+        let yes = ScaffoldOptions {
+            assume_yes: true,
+            ..Default::default()
+        };
+        let chosen = yes
+            .choose(&mut no_answers(), None, "Manager?", &["npm", "pnpm"])
+            .unwrap();
+        assert_eq!(chosen, "npm");
+
+        // A single option needs no question, even interactively.
+        let opts = ScaffoldOptions::default();
+        let chosen = opts
+            .choose(&mut no_answers(), None, "Manager?", &["bun"])
+            .unwrap();
+        assert_eq!(chosen, "bun");
+    }
+
+    #[test]
+    fn choose_asks_by_number_or_name() {
+        let opts = ScaffoldOptions::default();
+        let mut prompter = ScriptedPrompter::new(["2", "yarn"]);
+        let options = ["npm", "pnpm", "yarn"];
+        assert_eq!(
+            opts.choose(&mut prompter, None, "Manager?", &options)
+                .unwrap(),
+            "pnpm"
+        );
+        assert_eq!(
+            opts.choose(&mut prompter, None, "Manager?", &options)
+                .unwrap(),
+            "yarn"
+        );
+    }
+
+    #[test]
+    fn target_accepts_a_new_directory() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("app");
+        let opts = ScaffoldOptions::default();
+        let target = target(
+            ProgrammingLanguage::Rust,
+            dir.to_str().unwrap(),
+            &opts,
+            &mut no_answers(),
+        )
+        .unwrap();
+        assert_eq!(target.dir, temp.path().canonicalize().unwrap().join("app"));
+        assert!(!target.merge);
+    }
+
+    #[test]
+    fn target_refuses_a_non_empty_directory_by_default() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("app");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("README.md"), "hi").unwrap();
+        let yes = ScaffoldOptions {
+            assume_yes: true,
+            ..Default::default()
+        };
+        assert!(target_err(&dir, &yes).contains("--if-exists merge"));
+
+        let mut prompter = ScriptedPrompter::new(["merge"]);
+        let opts = ScaffoldOptions::default();
+        let target = target(
+            ProgrammingLanguage::Rust,
+            dir.to_str().unwrap(),
+            &opts,
+            &mut prompter,
+        )
+        .unwrap();
+        assert!(target.merge);
+    }
+
+    #[test]
+    fn target_offers_a_valid_name() {
+        let temp = tempfile::tempdir().unwrap();
+        let name = "My Project";
+        let suggestion = naming::check_project_name(ProgrammingLanguage::Rust, name)
+            .unwrap_err()
+            .suggestion
+            .unwrap();
+        let dir = temp.path().join(name);
+
+        let mut prompter = ScriptedPrompter::new(["y"]);
+        let opts = ScaffoldOptions::default();
+        let target = target(
+            ProgrammingLanguage::Rust,
+            dir.to_str().unwrap(),
+            &opts,
+            &mut prompter,
+        )
+        .unwrap();
+        assert_eq!(target.dir.file_name().unwrap(), suggestion.as_str());
+
+        // With --yes there's nobody to accept the suggestion.
+        let yes = ScaffoldOptions {
+            assume_yes: true,
+            ..Default::default()
+        };
+        assert!(target_err(&dir, &yes).contains("can't be used as a Rust project name"));
+    }
+
+    fn target_err(dir: &Path, opts: &ScaffoldOptions) -> String {
+        target(
+            ProgrammingLanguage::Rust,
+            dir.to_str().unwrap(),
+            opts,
+            &mut no_answers(),
+        )
+        .err()
+        .unwrap()
+        .to_string()
+    }
+}
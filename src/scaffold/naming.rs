@@ -0,0 +1,708 @@
+use std::fmt;
+
+use crate::state::languages::ProgrammingLanguage;
+use crate::template::render::{kebab_case, snake_case};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+/// Names Cargo refuses because they clash with the standard library or its build directories.
+const CARGO_RESERVED: &[&str] = &[
+    "alloc",
+    "build",
+    "core",
+    "deps",
+    "examples",
+    "incremental",
+    "proc-macro",
+    "proc_macro",
+    "std",
+    "test",
+];
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+/// Standard library modules a package is likely to be named after by accident. Shadowing one
+/// breaks imports of it anywhere in the project.
+const PYTHON_STDLIB: &[&str] = &[
+    "abc",
+    "array",
+    "ast",
+    "code",
+    "collections",
+    "copy",
+    "csv",
+    "email",
+    "enum",
+    "html",
+    "http",
+    "io",
+    "json",
+    "logging",
+    "math",
+    "os",
+    "parser",
+    "queue",
+    "random",
+    "re",
+    "select",
+    "signal",
+    "site",
+    "socket",
+    "ssl",
+    "string",
+    "sys",
+    "test",
+    "time",
+    "token",
+    "types",
+    "typing",
+    "xml",
+];
+const JAVA_KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+const KOTLIN_KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+const C_KEYWORDS: &[&str] = &[
+    "auto", "bool", "break", "case", "char", "const", "continue", "default", "do", "double",
+    "else", "enum", "extern", "false", "float", "for", "goto", "if", "inline", "int", "long",
+    "register", "restrict", "return", "short", "signed", "sizeof", "static", "struct", "switch",
+    "true", "typedef", "union", "unsigned", "void", "volatile", "while",
+];
+const CPP_KEYWORDS: &[&str] = &[
+    "alignas",
+    "alignof",
+    "and",
+    "asm",
+    "catch",
+    "class",
+    "concept",
+    "constexpr",
+    "delete",
+    "explicit",
+    "export",
+    "friend",
+    "module",
+    "mutable",
+    "namespace",
+    "new",
+    "noexcept",
+    "not",
+    "nullptr",
+    "operator",
+    "or",
+    "private",
+    "protected",
+    "public",
+    "requires",
+    "template",
+    "this",
+    "throw",
+    "try",
+    "typename",
+    "using",
+    "virtual",
+    "xor",
+];
+/// Device names Windows won't create a file or directory with, with or without an extension.
+const WINDOWS_RESERVED: &[&str] = &[
+    "aux", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8", "com9", "con", "lpt1",
+    "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9", "nul", "prn",
+];
+
+/// Why a name can't be used, and a similar one that can.
+#[derive(Debug)]
+pub struct InvalidName {
+    pub reason: String,
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for InvalidName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.suggestion {
+            Some(suggestion) => write!(f, "{}; try '{}'", self.reason, suggestion),
+            None => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+/// Checks a project name against `lang`'s rules. Besides naming the directory, the name becomes
+/// the crate, package or module name, or is derived into one as templates do.
+pub fn check_project_name(lang: ProgrammingLanguage, name: &str) -> Result<(), InvalidName> {
+    match problem(lang, name) {
+        Some(reason) => Err(InvalidName {
+            reason,
+            suggestion: suggest(lang, name),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Checks a dotted Java or Kotlin package name such as `com.example.app`.
+pub fn check_package(lang: ProgrammingLanguage, package: &str) -> Result<(), InvalidName> {
+    let reason = package
+        .split('.')
+        .find_map(|segment| jvm_identifier(lang, segment));
+    match reason {
+        Some(reason) => Err(InvalidName {
+            reason,
+            suggestion: None,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Checks a Go module path such as `github.com/alice/app`, following `go mod init`'s rules.
+pub fn check_module_path(path: &str) -> Result<(), InvalidName> {
+    let reason = if path.starts_with('/') || path.ends_with('/') {
+        Some("module paths can't start or end with '/'".to_string())
+    } else if path.starts_with('-') {
+        Some("module paths can't start with '-'".to_string())
+    } else {
+        path.split('/').find_map(go_path_element)
+    };
+    match reason {
+        Some(reason) => Err(InvalidName {
+            reason,
+            suggestion: None,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn problem(lang: ProgrammingLanguage, name: &str) -> Option<String> {
+    if name.is_empty() {
+        return Some("the name is empty".to_string());
+    }
+    let stem = name
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if WINDOWS_RESERVED.contains(&stem.as_str()) {
+        return Some(format!("'{}' is reserved on Windows", name));
+    }
+    match lang {
+        ProgrammingLanguage::Rust => rust_crate(name),
+        ProgrammingLanguage::Python => python_project(name),
+        ProgrammingLanguage::Java | ProgrammingLanguage::Kotlin => {
+            jvm_identifier(lang, &snake_case(name))
+        }
+        ProgrammingLanguage::C | ProgrammingLanguage::CPlusPlus => {
+            c_identifier(lang, &snake_case(name))
+        }
+        ProgrammingLanguage::Go => go_path_element(name).or_else(|| {
+            name.starts_with('-')
+                .then(|| "module paths can't start with '-'".to_string())
+        }),
+        ProgrammingLanguage::JavaScript => npm_package(name),
+        ProgrammingLanguage::CSharp | ProgrammingLanguage::Swift => module_name(name),
+    }
+}
+
+/// Turns `name` into a kebab-case ASCII name that passes `lang`'s rules, if there is one.
+fn suggest(lang: ProgrammingLanguage, name: &str) -> Option<String> {
+    let ascii: String = name.chars().filter(char::is_ascii).collect();
+    let mut candidate = kebab_case(&ascii);
+    if candidate.is_empty() {
+        return None;
+    }
+    // Covers leading digits as well as keywords and reserved names.
+    if problem(lang, &candidate).is_some() {
+        candidate = format!("my-{}", candidate);
+    }
+    problem(lang, &candidate).is_none().then_some(candidate)
+}
+
+fn rust_crate(name: &str) -> Option<String> {
+    if let Some(c) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '-' && *c != '_')
+    {
+        return Some(format!(
+            "crate names may only contain letters, digits, '-' and '_', not '{}'",
+            c
+        ));
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Some("crate names can't start with a digit".to_string());
+    }
+    if RUST_KEYWORDS.contains(&name) {
+        return Some(format!("'{}' is a Rust keyword", name));
+    }
+    if CARGO_RESERVED.contains(&name) {
+        return Some(format!("'{}' is reserved by Cargo", name));
+    }
+    None
+}
+
+fn python_project(name: &str) -> Option<String> {
+    // PEP 508: letters, digits, '.', '-' and '_', starting and ending with a letter or digit.
+    if let Some(c) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !matches!(c, '.' | '-' | '_'))
+    {
+        return Some(format!(
+            "Python project names may only contain letters, digits, '.', '-' and '_', not '{}'",
+            c
+        ));
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        || !name.ends_with(|c: char| c.is_ascii_alphanumeric())
+    {
+        return Some("Python project names must start and end with a letter or digit".to_string());
+    }
+    let package = snake_case(name);
+    if package.starts_with(|c: char| c.is_ascii_digit()) {
+        return Some(format!(
+            "the package name '{}' would start with a digit",
+            package
+        ));
+    }
+    if PYTHON_KEYWORDS.contains(&package.as_str()) {
+        return Some(format!(
+            "the package name '{}' is a Python keyword",
+            package
+        ));
+    }
+    if PYTHON_STDLIB.contains(&package.as_str()) {
+        return Some(format!(
+            "the package name '{}' would shadow the standard library module",
+            package
+        ));
+    }
+    None
+}
+
+fn jvm_identifier(lang: ProgrammingLanguage, segment: &str) -> Option<String> {
+    let keywords = match lang {
+        ProgrammingLanguage::Kotlin => KOTLIN_KEYWORDS,
+        _ => JAVA_KEYWORDS,
+    };
+    if segment.is_empty() {
+        return Some("package names can't have empty segments".to_string());
+    }
+    if let Some(c) = segment
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && *c != '_')
+    {
+        return Some(format!(
+            "package segments may only contain letters, digits and '_', not '{}'",
+            c
+        ));
+    }
+    if segment.starts_with(|c: char| c.is_ascii_digit()) {
+        return Some(format!(
+            "the package segment '{}' starts with a digit",
+            segment
+        ));
+    }
+    if keywords.contains(&segment) {
+        return Some(format!(
+            "the package segment '{}' is a {:?} keyword",
+            segment, lang
+        ));
+    }
+    None
+}
+
+fn c_identifier(lang: ProgrammingLanguage, identifier: &str) -> Option<String> {
+    if identifier.is_empty() {
+        return Some("the name has no letters or digits".to_string());
+    }
+    if !identifier.is_ascii() {
+        return Some(format!(
+            "the identifier '{}' has non-ASCII characters",
+            identifier
+        ));
+    }
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        return Some(format!(
+            "the identifier '{}' would start with a digit",
+            identifier
+        ));
+    }
+    let is_keyword = C_KEYWORDS.contains(&identifier)
+        || (lang == ProgrammingLanguage::CPlusPlus && CPP_KEYWORDS.contains(&identifier));
+    if is_keyword {
+        return Some(format!("'{}' is a keyword", identifier));
+    }
+    None
+}
+
+fn go_path_element(element: &str) -> Option<String> {
+    if element.is_empty() {
+        return Some("module paths can't have empty elements".to_string());
+    }
+    if let Some(c) = element
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !matches!(c, '-' | '.' | '_' | '~'))
+    {
+        return Some(format!(
+            "module path elements may only contain letters, digits, '-', '.', '_' and '~', not '{}'",
+            c
+        ));
+    }
+    if element.starts_with('.') || element.ends_with('.') {
+        return Some("module path elements can't start or end with '.'".to_string());
+    }
+    None
+}
+
+fn npm_package(name: &str) -> Option<String> {
+    if let Some(c) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !matches!(c, '-' | '.' | '_' | '~'))
+    {
+        return Some(format!(
+            "npm package names may only contain letters, digits, '-', '.', '_' and '~', not '{}'",
+            c
+        ));
+    }
+    if name.chars().any(|c| c.is_ascii_uppercase()) {
+        return Some("npm package names can't contain uppercase letters".to_string());
+    }
+    if name.starts_with('.') || name.starts_with('_') {
+        return Some("npm package names can't start with '.' or '_'".to_string());
+    }
+    if name.len() > 214 {
+        return Some("npm package names can't be longer than 214 characters".to_string());
+    }
+    if matches!(name, "node_modules" | "favicon.ico") {
+        return Some(format!("'{}' is reserved by npm", name));
+    }
+    None
+}
+
+fn module_name(name: &str) -> Option<String> {
+    if let Some(c) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !matches!(c, '-' | '.' | '_'))
+    {
+        return Some(format!(
+            "names may only contain letters, digits, '-', '.' and '_', not '{}'",
+            c
+        ));
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        return Some("names must start with a letter or '_'".to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ProgrammingLanguage::*;
+
+    #[test]
+    fn rust_crate_rules() {
+        let cases = [
+            ("my-app", None),
+            ("my_app", None),
+            ("app2", None),
+            ("my app", Some("not ' '")),
+            ("1app", Some("start with a digit")),
+            ("self", Some("Rust keyword")),
+            ("async", Some("Rust keyword")),
+            ("test", Some("reserved by Cargo")),
+            ("proc-macro", Some("reserved by Cargo")),
+        ];
+        for (name, reason) in cases {
+            assert_reason(rust_crate(name), reason, name);
+        }
+    }
+
+    #[test]
+    fn python_project_rules() {
+        let cases = [
+            ("my-app", None),
+            ("my.app", None),
+            ("app_2", None),
+            ("my app", Some("not ' '")),
+            ("-app", Some("start and end with a letter or digit")),
+            ("app-", Some("start and end with a letter or digit")),
+            ("1app", Some("would start with a digit")),
+            ("class", Some("Python keyword")),
+            ("test", Some("shadow the standard library")),
+            ("Json", Some("shadow the standard library")),
+        ];
+        for (name, reason) in cases {
+            assert_reason(python_project(name), reason, name);
+        }
+    }
+
+    #[test]
+    fn jvm_identifier_rules() {
+        let cases = [
+            (Java, "app", None),
+            (Java, "my_app", None),
+            (Java, "fun", None),
+            (Kotlin, "fun", Some("Kotlin keyword")),
+            (Java, "class", Some("Java keyword")),
+            (Kotlin, "new", None),
+            (Java, "my-app", Some("not '-'")),
+            (Java, "1app", Some("starts with a digit")),
+            (Java, "", Some("empty segments")),
+        ];
+        for (lang, segment, reason) in cases {
+            assert_reason(jvm_identifier(lang, segment), reason, segment);
+        }
+    }
+
+    #[test]
+    fn c_identifier_rules() {
+        let cases = [
+            (C, "my_app", None),
+            (C, "class", None),
+            (CPlusPlus, "class", Some("keyword")),
+            (C, "int", Some("keyword")),
+            (C, "2d", Some("start with a digit")),
+            (C, "caf\u{e9}", Some("non-ASCII")),
+            (C, "", Some("no letters or digits")),
+        ];
+        for (lang, identifier, reason) in cases {
+            assert_reason(c_identifier(lang, identifier), reason, identifier);
+        }
+    }
+
+    #[test]
+    fn go_path_element_rules() {
+        let cases = [
+            ("app", None),
+            ("my-app_v2~x", None),
+            ("github.com", None),
+            ("", Some("empty elements")),
+            ("my app", Some("not ' '")),
+            (".app", Some("start or end with '.'")),
+            ("app.", Some("start or end with '.'")),
+        ];
+        for (element, reason) in cases {
+            assert_reason(go_path_element(element), reason, element);
+        }
+    }
+
+    #[test]
+    fn npm_package_rules() {
+        let long = "a".repeat(215);
+        let cases = [
+            ("my-app", None),
+            ("my.app_2", None),
+            ("MyApp", Some("uppercase")),
+            ("my app", Some("not ' '")),
+            ("_app", Some("can't start with '.' or '_'")),
+            (".app", Some("can't start with '.' or '_'")),
+            ("node_modules", Some("reserved by npm")),
+            (long.as_str(), Some("longer than 214")),
+        ];
+        for (name, reason) in cases {
+            assert_reason(npm_package(name), reason, name);
+        }
+    }
+
+    #[test]
+    fn module_name_rules() {
+        let cases = [
+            ("MyApp", None),
+            ("_app", None),
+            ("My.App-2", None),
+            ("1app", Some("start with a letter or '_'")),
+            ("-app", Some("start with a letter or '_'")),
+            ("my app", Some("not ' '")),
+        ];
+        for (name, reason) in cases {
+            assert_reason(module_name(name), reason, name);
+        }
+    }
+
+    #[test]
+    fn windows_device_names_are_rejected_everywhere() {
+        for lang in [Rust, Python, Java, C, Go, JavaScript, CSharp] {
+            for name in ["con", "CON", "nul.txt", "com1"] {
+                let error = check_project_name(lang, name).unwrap_err();
+                assert!(error.reason.contains("reserved on Windows"), "{}", name);
+            }
+            assert!(check_project_name(lang, "console").is_ok());
+        }
+    }
+
+    #[test]
+    fn suggestions_follow_each_rule() {
+        let cases = [
+            (Rust, "test", Some("my-test")),
+            (Rust, "self", Some("my-self")),
+            (Rust, "My App", Some("my-app")),
+            (Rust, "con", Some("my-con")),
+            (Python, "test", Some("my-test")),
+            (Python, "1app", Some("my-1app")),
+            (Java, "class", Some("my-class")),
+            (Kotlin, "fun", Some("my-fun")),
+            (CPlusPlus, "new", Some("my-new")),
+            (Go, "my app", Some("my-app")),
+            (JavaScript, "MyApp", Some("my-app")),
+            (JavaScript, "_private", Some("private")),
+            (CSharp, "1app", Some("my-1app")),
+            (Rust, "\u{65e5}\u{672c}", None),
+        ];
+        for (lang, name, suggestion) in cases {
+            let error = check_project_name(lang, name).unwrap_err();
+            assert_eq!(
+                error.suggestion.as_deref(),
+                suggestion,
+                "{:?} {}",
+                lang,
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn valid_names_pass() {
+        for (lang, name) in [
+            (Rust, "my-app"),
+            (Python, "my-app"),
+            (Java, "my-app"),
+            (Kotlin, "MyApp"),
+            (C, "my-app"),
+            (Go, "my-app"),
+            (JavaScript, "my-app"),
+            (Swift, "MyApp"),
+        ] {
+            assert!(
+                check_project_name(lang, name).is_ok(),
+                "{:?} {}",
+                lang,
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn packages_are_checked_per_segment() {
+        assert!(check_package(Java, "com.example.app").is_ok());
+        assert!(check_package(Kotlin, "com.example.new").is_ok());
+        for package in [
+            "com.example.new",
+            "com..app",
+            "com.1st.app",
+            "com.my-co.app",
+        ] {
+            assert!(check_package(Java, package).is_err(), "{}", package);
+        }
+    }
+
+    #[test]
+    fn module_paths_follow_go_mod_init() {
+        for path in ["app", "github.com/alice/app", "example.com/my-app/v2"] {
+            assert!(check_module_path(path).is_ok(), "{}", path);
+        }
+        for path in [
+            "/app",
+            "app/",
+            "-app",
+            "github.com//app",
+            "github.com/.hidden",
+            "my app",
+        ] {
+            assert!(check_module_path(path).is_err(), "{}", path);
+        }
+    }
+
+    fn assert_reason(actual: Option<String>, expected: Option<&str>, name: &str) {
+        match (actual, expected) {
+            (None, None) => {}
+            (Some(reason), Some(expected)) => {
+                assert!(reason.contains(expected), "{}: {}", name, reason)
+            }
+            (actual, expected) => panic!("{}: got {:?}, expected {:?}", name, actual, expected),
+        }
+    }
+}
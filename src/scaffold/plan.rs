@@ -0,0 +1,594 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use color_eyre::eyre::{bail, eyre};
+
+use crate::database::manage::setup_database;
+use crate::database::model::Project;
+use crate::post_setup;
+use crate::post_setup::hooks::{self, Hook, OnFailure, Stage};
+use crate::scaffold::{ScaffoldOptions, Target};
+use crate::state::languages::ProgrammingLanguage;
+use crate::template::render::Variables;
+use crate::template::Template;
+use crate::ui::prompt::Prompter;
+
+type Action = Box<dyn FnOnce(&Path) -> color_eyre::Result<()>>;
+
+/// The changes a scaffolder makes to disk, run in order once all questions are answered.
+///
+/// If a step fails, everything done so far is undone: the project directory and any parents are
+/// deleted if the plan created them, otherwise every file and directory added under it is, at
+/// any depth. Files in an existing directory that a step overwrote are not restored. The
+/// project is registered in the database last, so a failed plan never leaves a row behind. Optional steps, like installing dependencies, only warn
+/// when they fail since they're easy to rerun.
+///
+/// When merging into a non-empty directory, rendering and writing files skip any that already
+/// exist, though commands the plan runs may still change them.
+pub struct Plan {
+    project_dir: PathBuf,
+    merge: bool,
+    steps: Vec<Step>,
+}
+
+struct Step {
+    description: String,
+    optional: bool,
+    action: Action,
+}
+
+/// What to undo if a step fails.
+struct Rollback {
+    /// Directories the plan creates, innermost first: the project directory and any of its
+    /// parents that were missing.
+    created_dirs: Vec<PathBuf>,
+    /// Every path under the project directory from before the plan ran, so the ones a step
+    /// adds, at any depth, can be told apart.
+    existing: HashSet<PathBuf>,
+}
+
+impl Plan {
+    /// Starts a plan for the project at `target`, see [`crate::scaffold::target`].
+    pub fn new(target: &Target) -> Plan {
+        Plan {
+            project_dir: target.dir.clone(),
+            merge: target.merge,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Adds a step that runs `action` with the project directory.
+    pub fn step(
+        &mut self,
+        description: impl Into<String>,
+        action: impl FnOnce(&Path) -> color_eyre::Result<()> + 'static,
+    ) -> &mut Plan {
+        self.push(description.into(), false, Box::new(action))
+    }
+
+    /// Like [`Plan::step`], but a failure is reported instead of rolling back.
+    pub fn optional_step(
+        &mut self,
+        description: impl Into<String>,
+        action: impl FnOnce(&Path) -> color_eyre::Result<()> + 'static,
+    ) -> &mut Plan {
+        self.push(description.into(), true, Box::new(action))
+    }
+
+    /// Adds a step running `program` inside the project directory.
+    pub fn command(&mut self, program: &str, args: &[&str]) -> &mut Plan {
+        let (description, action) = command(program, args);
+        self.push(description, false, action)
+    }
+
+    /// Like [`Plan::command`], but a failure is reported instead of rolling back.
+    pub fn optional_command(&mut self, program: &str, args: &[&str]) -> &mut Plan {
+        let (description, action) = command(program, args);
+        self.push(description, true, action)
+    }
+
+    /// Adds a step rendering `template` into the project directory, followed by a command for
+    /// each of the manifest's steps. Fails if a step's arguments don't render.
+    pub fn render(&mut self, template: Template, vars: Variables) -> color_eyre::Result<&mut Plan> {
+        let commands = template.commands(&vars)?;
+        let description = format!("Render the {} template", template.manifest.name);
+        let merge = self.merge;
+        self.step(description, move |dir| {
+            template.render(&vars, dir, merge)?;
+            Ok(())
+        });
+        for (args, optional) in commands {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            if optional {
+                self.optional_command(args[0], &args[1..]);
+            } else {
+                self.command(args[0], &args[1..]);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Adds a step writing `contents` to `path`, relative to the project directory.
+    pub fn write(&mut self, path: &str, contents: impl Into<Vec<u8>>) -> &mut Plan {
+        let path = path.to_string();
+        let contents = contents.into();
+        let merge = self.merge;
+        self.step(format!("Write {}", path), move |dir| {
+            let target = dir.join(&path);
+            if merge && target.exists() {
+                println!("Keeping existing {}", target.display());
+                return Ok(());
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(target, contents)?;
+            Ok(())
+        })
+    }
+
+    fn push(&mut self, description: String, optional: bool, action: Action) -> &mut Plan {
+        self.steps.push(Step {
+            description,
+            optional,
+            action,
+        });
+        self
+    }
+
+    /// Runs the plan and registers the project as `lang`, or only prints the plan with
+    /// `--dry-run`. Opens the editor afterwards if that's enabled in the settings.
+    ///
+    /// The `pre_scaffold` hooks run first, before anything is written, and the `post_scaffold`
+    /// hooks run as the last steps before registering, so an `abort` hook failing there rolls
+    /// the project back.
+    pub fn execute(
+        mut self,
+        lang: ProgrammingLanguage,
+        opts: &ScaffoldOptions,
+        prompter: &mut dyn Prompter,
+    ) -> color_eyre::Result<()> {
+        // The plan and the steps' output go to the normal screen.
+        prompter.suspend();
+        self.post_scaffold_hooks(hooks::configured(Stage::PostScaffold, lang), lang);
+        let name = self
+            .project_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| eyre!("Failed to get project name"))?;
+        let project_name = name.clone();
+        // Last, so a failed plan never leaves a row behind.
+        self.step("Register the project in the database", move |dir| {
+            let mut project = Project::new(lang, name, dir);
+            Ok(project.insert(&setup_database()?)?)
+        });
+        let project_dir = self.project_dir.clone();
+        let pre_hooks = hooks::configured(Stage::PreScaffold, lang);
+        if !self.run(lang, &pre_hooks, opts.dry_run)? {
+            return Ok(());
+        }
+
+        println!("Project {} created successfully", project_name);
+
+        if let Err(e) = post_setup::open(&project_dir, lang, opts, prompter) {
+            eprintln!("Warning: Opening the project failed: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Adds `hooks` as steps; `warn` hooks are optional.
+    fn post_scaffold_hooks(&mut self, hooks: Vec<Hook>, lang: ProgrammingLanguage) {
+        for hook in hooks {
+            let description = hook.description(Stage::PostScaffold);
+            let optional = hook.on_failure == OnFailure::Warn;
+            self.push(
+                description,
+                optional,
+                Box::new(move |dir| hook.run(Stage::PostScaffold, dir, lang)),
+            );
+        }
+    }
+
+    /// Runs `pre_hooks` and then the steps, rolling back if a step fails. With `dry_run`, only
+    /// prints what would be done and returns `false`.
+    pub(super) fn run(
+        self,
+        lang: ProgrammingLanguage,
+        pre_hooks: &[Hook],
+        dry_run: bool,
+    ) -> color_eyre::Result<bool> {
+        let create_dir = !self.project_dir.exists();
+        let mut descriptions = Vec::new();
+        for hook in pre_hooks {
+            let description = hook.description(Stage::PreScaffold);
+            if hook.on_failure == OnFailure::Warn {
+                descriptions.push(format!("{} (optional)", description));
+            } else {
+                descriptions.push(description);
+            }
+        }
+        if create_dir {
+            descriptions.push(format!("Create directory {}", self.project_dir.display()));
+        }
+        for step in &self.steps {
+            if step.optional {
+                descriptions.push(format!("{} (optional)", step.description));
+            } else {
+                descriptions.push(step.description.clone());
+            }
+        }
+
+        if dry_run {
+            println!("Plan for {}:", self.project_dir.display());
+            for (i, description) in descriptions.iter().enumerate() {
+                println!("  {}. {}", i + 1, description);
+            }
+            println!("Dry run, nothing was changed.");
+            return Ok(false);
+        }
+
+        let total = descriptions.len();
+        let mut number = 1;
+        for hook in pre_hooks {
+            if let Err(e) = hook.run(Stage::PreScaffold, &self.project_dir, lang) {
+                let description = hook.description(Stage::PreScaffold);
+                match hook.on_failure {
+                    OnFailure::Warn => eprintln!("Warning: {} failed: {}", description, e),
+                    OnFailure::Abort => bail!(
+                        "Step {} of {} ({}) failed: {}. Nothing was changed.",
+                        number,
+                        total,
+                        description,
+                        e
+                    ),
+                }
+            }
+            number += 1;
+        }
+        let rollback = Rollback::record(&self.project_dir)?;
+        if create_dir {
+            if let Err(e) = fs::create_dir_all(&self.project_dir) {
+                rollback.undo(&self.project_dir);
+                bail!(
+                    "Step {} of {} ({}) failed: {}",
+                    number,
+                    total,
+                    descriptions[number - 1],
+                    e
+                );
+            }
+            number += 1;
+        }
+
+        for step in self.steps {
+            if let Err(e) = (step.action)(&self.project_dir) {
+                if step.optional {
+                    eprintln!(
+                        "Warning: {} failed: {}. Run it again inside {} once the problem is fixed.",
+                        step.description,
+                        e,
+                        self.project_dir.display()
+                    );
+                } else {
+                    rollback.undo(&self.project_dir);
+                    bail!(
+                        "Step {} of {} ({}) failed: {}. The changes made so far were rolled back.",
+                        number,
+                        total,
+                        step.description,
+                        e
+                    );
+                }
+            }
+            number += 1;
+        }
+        Ok(true)
+    }
+}
+
+impl Rollback {
+    /// Notes what's on disk before the plan changes anything at `project_dir`.
+    fn record(project_dir: &Path) -> color_eyre::Result<Rollback> {
+        let created_dirs = project_dir
+            .ancestors()
+            .take_while(|dir| !dir.exists())
+            .map(Path::to_path_buf)
+            .collect();
+        let mut existing = HashSet::new();
+        if project_dir.exists() {
+            list_tree(project_dir, &mut existing)?;
+        }
+        Ok(Rollback {
+            created_dirs,
+            existing,
+        })
+    }
+
+    /// Best effort: anything that can't be removed is reported so it can be cleaned up by hand.
+    fn undo(&self, project_dir: &Path) {
+        if self.created_dirs.is_empty() {
+            let mut now = HashSet::new();
+            if let Err(e) = list_tree(project_dir, &mut now) {
+                eprintln!(
+                    "Warning: Failed to clean up {}: {}",
+                    project_dir.display(),
+                    e
+                );
+                return;
+            }
+            let added: HashSet<&PathBuf> = now.difference(&self.existing).collect();
+            for path in &added {
+                // Removing a new directory takes everything in it along.
+                if !path
+                    .parent()
+                    .is_some_and(|parent| added.contains(&parent.to_path_buf()))
+                {
+                    remove(path);
+                }
+            }
+            return;
+        }
+        remove(project_dir);
+        // Parents created for the project, innermost first. One that's no longer empty got
+        // something else in the meantime and is left alone.
+        for dir in &self.created_dirs[1..] {
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Adds every path under `dir` to `paths`, without following symlinks.
+fn list_tree(dir: &Path, paths: &mut HashSet<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            list_tree(&path, paths)?;
+        }
+        paths.insert(path);
+    }
+    Ok(())
+}
+
+fn remove(path: &Path) {
+    let removed = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = removed {
+        eprintln!("Warning: Failed to remove {}: {}", path.display(), e);
+    }
+}
+
+fn command(program: &str, args: &[&str]) -> (String, Action) {
+    let description = if args.is_empty() {
+        format!("Run `{}`", program)
+    } else {
+        format!("Run `{} {}`", program, args.join(" "))
+    };
+    let program = program.to_string();
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let action: Action = Box::new(move |dir| {
+        let status = Command::new(&program)
+            .args(&args)
+            .current_dir(dir)
+            .status()
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => eyre!("{} is not installed", program),
+                _ => eyre!("couldn't start {}: {}", program, e),
+            })?;
+        if !status.success() {
+            bail!("exit code: {:?}", status.code());
+        }
+        Ok(())
+    });
+    (description, action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(dir: &Path, merge: bool) -> Plan {
+        Plan::new(&Target {
+            dir: dir.to_path_buf(),
+            merge,
+        })
+    }
+
+    fn run(plan: Plan, dry_run: bool) -> color_eyre::Result<bool> {
+        plan.run(ProgrammingLanguage::Rust, &[], dry_run)
+    }
+
+    #[test]
+    fn new_directory_is_created_with_its_parents() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("parent/app");
+        let mut plan = plan(&dir, false);
+        plan.write("src/main.rs", "fn main() {}\n");
+
+        assert!(run(plan, false).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.join("src/main.rs")).unwrap(),
+            "fn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn failing_step_removes_the_new_directory_and_parents() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("parent/app");
+        let mut plan = plan(&dir, false);
+        plan.write("README.md", "# app\n")
+            .step("Fail", |_| bail!("broken"));
+
+        let error = run(plan, false).unwrap_err().to_string();
+        assert!(
+            error.contains("Step 3 of 3 (Fail) failed: broken"),
+            "{}",
+            error
+        );
+        assert!(!temp.path().join("parent").exists());
+    }
+
+    #[test]
+    fn failing_step_keeps_what_a_merge_found() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "// mine\n").unwrap();
+        fs::write(dir.join("notes.txt"), "keep\n").unwrap();
+        let mut plan = plan(dir, true);
+        plan.write("src/lib.rs", "// template\n")
+            .write("src/main.rs", "fn main() {}\n")
+            .write(".github/workflows/ci.yml", "on: push\n")
+            .step("Fail", |_| bail!("broken"));
+
+        assert!(run(plan, false).is_err());
+        assert_eq!(
+            fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+            "// mine\n"
+        );
+        assert!(dir.join("notes.txt").exists());
+        assert!(!dir.join("src/main.rs").exists());
+        assert!(!dir.join(".github").exists());
+    }
+
+    #[test]
+    fn optional_step_failure_keeps_going() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("app");
+        let mut plan = plan(&dir, false);
+        plan.optional_step("Fail", |_| bail!("broken"))
+            .write("README.md", "# app\n");
+
+        assert!(run(plan, false).unwrap());
+        assert!(dir.join("README.md").exists());
+    }
+
+    #[test]
+    fn failing_template_step_rolls_back() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("template");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(
+            source.join("template.toml"),
+            "name = \"broken\"\n\
+             [[steps]]\n\
+             run = [\"projector-test-missing-tool\", \"--optional\"]\n\
+             optional = true\n\
+             [[steps]]\n\
+             run = [\"projector-test-missing-tool\", \"{{ project_name }}\"]\n",
+        )
+        .unwrap();
+        fs::write(source.join("README.md"), "# {{ project_name }}\n").unwrap();
+        let template = Template::from_dir(&source).unwrap();
+        let dir = temp.path().join("app");
+        let mut plan = plan(&dir, false);
+        plan.render(template, crate::template::render::name_variables("app"))
+            .unwrap();
+
+        let error = run(plan, false).unwrap_err().to_string();
+        assert!(
+            error.contains(
+                "(Run `projector-test-missing-tool app`) failed: projector-test-missing-tool is not installed"
+            ),
+            "{}",
+            error
+        );
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn dry_run_changes_nothing() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("app");
+        let mut plan = plan(&dir, false);
+        plan.write("README.md", "# app\n")
+            .step("Fail", |_| bail!("not run"));
+
+        assert!(!run(plan, true).unwrap());
+        assert!(!dir.exists());
+    }
+
+    #[cfg(unix)]
+    fn hook(command: &str, on_failure: OnFailure) -> Hook {
+        Hook {
+            command: command.to_string(),
+            timeout_secs: 10,
+            on_failure,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_abort_pre_hook_stops_before_anything_is_written() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("app");
+        let mut plan = plan(&dir, false);
+        plan.write("README.md", "# app\n");
+
+        let pre_hooks = [hook("exit 1", OnFailure::Abort)];
+        let error = plan
+            .run(ProgrammingLanguage::Rust, &pre_hooks, false)
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("Nothing was changed"),
+            "{}",
+            error
+        );
+        assert!(!dir.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_warn_pre_hook_keeps_going() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("app");
+        let mut plan = plan(&dir, false);
+        plan.write("README.md", "# app\n");
+
+        let pre_hooks = [hook("exit 1", OnFailure::Warn)];
+        assert!(plan
+            .run(ProgrammingLanguage::Rust, &pre_hooks, false)
+            .unwrap());
+        assert!(dir.join("README.md").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_abort_post_hook_rolls_back_and_warn_does_not() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("app");
+        let mut aborted = plan(&dir, false);
+        aborted.write("README.md", "# app\n");
+        aborted.post_scaffold_hooks(
+            vec![
+                hook("touch hooked", OnFailure::Abort),
+                hook("exit 1", OnFailure::Abort),
+            ],
+            ProgrammingLanguage::Rust,
+        );
+        assert!(run(aborted, false).is_err());
+        assert!(!dir.exists());
+
+        let mut warned = plan(&dir, false);
+        warned.write("README.md", "# app\n");
+        warned.post_scaffold_hooks(
+            vec![
+                hook("exit 1", OnFailure::Warn),
+                hook("touch hooked", OnFailure::Abort),
+            ],
+            ProgrammingLanguage::Rust,
+        );
+        assert!(run(warned, false).unwrap());
+        assert!(dir.join("hooked").exists());
+    }
+}
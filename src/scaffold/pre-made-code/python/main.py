@@ -1,5 +0,0 @@
-def main():
-    print("Hello from main!")
-
-if __name__ == "__main__":
-    main()
\ No newline at end of file
@@ -1,305 +1,235 @@
-use std::{env, env::temp_dir, fs, path::Path, process::Command};
+use std::{fs, path::Path, process::Command};
+
+use color_eyre::eyre::{bail, eyre};
+use serde_json::Value;
 
 use crate::{
-    database::model::Project,
+    install::{self, download::Downloader, plan::InstallPlan, Tool},
+    probe,
+    scaffold::{self, plan::Plan, ScaffoldOptions},
     state::languages::ProgrammingLanguage,
-    ui::{grab_string::ask, yn},
+    template,
+    ui::prompt::Prompter,
 };
 
-pub fn setup(project_name: Option<&str>) {
-    if env::var("FAKE_UNINSTALLED").is_ok() {
-        return;
-    }
-    if !is_python_installed() {
-        install_python().expect("Failed to install Python");
-        return;
+const BACKENDS: [&str; 5] = ["pip", "uv", "poetry", "hatch", "pdm"];
+
+pub fn setup(
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    if !probe::is_installed("python3") && !probe::is_installed("python") {
+        return install_python(opts, prompter);
     }
+    scaffold(project_name, opts, prompter)
+}
 
-    match project_name {
-        Some(name) => {
-            let path = Path::new(name);
-            if !path.exists() {
-                fs::create_dir(name).expect("Failed to create project directory");
-            }
-            let canonical_path = path.canonicalize().expect("Failed to get full path");
-            println!("Project {} created successfully", name);
-            if yn::ask("Create a virtual environment? (y/n)").unwrap_or(false) {
-                let python_cmd = if cfg!(target_os = "windows") {
-                    "python"
-                } else {
-                    "python3"
-                };
-                Command::new(python_cmd)
-                    .arg("-m")
-                    .arg("venv")
-                    .arg("venv")
-                    .arg("--upgrade-deps")
-                    .current_dir(&canonical_path)
-                    .status()
-                    .expect("Failed to create virtual environment");
-            }
-            let main_path = canonical_path.join("main.py");
-            fs::write(&main_path, include_str!("./pre-made-code/python/main.py"))
-                .expect("Failed to create main.py");
-            if yn::ask("Create requirements.txt? (y/n)").unwrap_or(false) {
-                let requirements_path = canonical_path.join("requirements.txt");
-                fs::write(&requirements_path, "").expect("Failed to create requirements.txt");
-            }
-            if yn::ask("Set up Git for this project? (y/n)").unwrap_or(false) {
-                Command::new("git")
-                    .arg("init")
-                    .current_dir(&canonical_path)
-                    .status()
-                    .expect("Failed to init git repository");
-                if yn::ask("Create a .gitignore file? (y/n)").unwrap_or(false) {
-                    let gitignore_path = canonical_path.join(".gitignore");
-                    fs::write(&gitignore_path, "venv/\n").expect("Failed to create .gitignore");
-                }
-            }
-            let mut project = Project::new(
-                ProgrammingLanguage::Python,
-                name.to_string(),
-                canonical_path.as_path(),
-            );
-            crate::post_setup::run_post_setup(&mut project).expect("Failed to run post setup");
-        }
-        None => {
-            let answer = ask("Project name? (leave blank to use current directory)")
-                .expect("Failed to get project name");
+/// Asks about each optional piece unless it was answered up front in `opts`, then writes the
+/// project and sets up its environment with the chosen packaging tool.
+fn scaffold(
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter.input("Project name? (leave blank to use current directory)")?,
+    };
+    let target = scaffold::target(ProgrammingLanguage::Python, &name, opts, prompter)?;
 
-            if answer.trim().is_empty() {
-                let path = Path::new(".");
-                let canonical_path = path.canonicalize().expect("Failed to get full path");
-                println!("Initialized current directory as a Python project");
-                if yn::ask("Create a virtual environment? (y/n)").unwrap_or(false) {
-                    let python_cmd = if cfg!(target_os = "windows") {
-                        "python"
-                    } else {
-                        "python3"
-                    };
-                    Command::new(python_cmd)
-                        .arg("-m")
-                        .arg("venv")
-                        .arg("venv")
-                        .arg("--upgrade-deps")
-                        .current_dir(&canonical_path)
-                        .status()
-                        .expect("Failed to create virtual environment");
-                }
-                let main_path = canonical_path.join("main.py");
-                fs::write(&main_path, include_str!("./pre-made-code/python/main.py"))
-                    .expect("Failed to create main.py");
-                if yn::ask("Create requirements.txt? (y/n)").unwrap_or(false) {
-                    let requirements_path = canonical_path.join("requirements.txt");
-                    fs::write(&requirements_path, "").expect("Failed to create requirements.txt");
-                }
-                if yn::ask("Set up Git for this project? (y/n)").unwrap_or(false) {
-                    Command::new("git")
-                        .arg("init")
-                        .current_dir(&canonical_path)
-                        .status()
-                        .expect("Failed to init git repository");
-                    if yn::ask("Create a .gitignore file? (y/n)").unwrap_or(false) {
-                        let gitignore_path = canonical_path.join(".gitignore");
-                        fs::write(&gitignore_path, "venv/\n").expect("Failed to create .gitignore");
-                    }
-                }
-                let mut project = Project::new(
-                    ProgrammingLanguage::Python,
-                    canonical_path
-                        .file_name()
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .to_string(),
-                    canonical_path.as_path(),
-                );
-                crate::post_setup::run_post_setup(&mut project).expect("Failed to run post setup");
-            } else {
-                let path = Path::new(answer.trim());
-                if !path.exists() {
-                    fs::create_dir(answer.trim()).expect("Failed to create project directory");
-                }
-                let canonical_path = path.canonicalize().expect("Failed to get full path");
-                println!("Project {} created successfully", answer.trim());
-                if yn::ask("Create a virtual environment? (y/n)").unwrap_or(false) {
-                    let python_cmd = if cfg!(target_os = "windows") {
-                        "python"
-                    } else {
-                        "python3"
-                    };
-                    let status = std::process::Command::new(python_cmd)
-                        .args(&["-m", "venv", "venv", "--upgrade-deps"])
-                        .current_dir(&canonical_path)
-                        .status()
-                        .expect("Failed to create virtual environment");
-                    if !status.success() {
-                        eprintln!(
-                            "Error: Creating the virtual environment failed (exit code: {:?})",
-                            status.code()
-                        );
-                    }
-                }
+    let backend = opts.choose(
+        prompter,
+        opts.package_manager.as_deref(),
+        "Which packaging tool should the project use?",
+        &BACKENDS,
+    )?;
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
 
-                let main_path = canonical_path.join("main.py");
-                fs::write(&main_path, include_str!("./pre-made-code/python/main.py"))
-                    .expect("Failed to create main.py");
-                if yn::ask("Create requirements.txt? (y/n)").unwrap_or(false) {
-                    let requirements_path = canonical_path.join("requirements.txt");
-                    fs::write(&requirements_path, "").expect("Failed to create requirements.txt");
-                }
-                if yn::ask("Set up Git for this project? (y/n)").unwrap_or(false) {
-                    Command::new("git")
-                        .arg("init")
-                        .current_dir(&canonical_path)
-                        .status()
-                        .expect("Failed to init git repository");
-                    if yn::ask("Create a .gitignore file? (y/n)").unwrap_or(false) {
-                        let gitignore_path = canonical_path.join(".gitignore");
-                        fs::write(&gitignore_path, "venv/\n").expect("Failed to create .gitignore");
-                    }
-                }
-                let mut project = Project::new(
-                    ProgrammingLanguage::Python,
-                    canonical_path
-                        .file_name()
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .to_string(),
-                    canonical_path.as_path(),
-                );
-                crate::post_setup::run_post_setup(&mut project).expect("Failed to run post setup");
-            }
-        }
+    let template = template::find("python-package")?
+        .ok_or_else(|| eyre!("No python-package template found"))?;
+    let mut vars = template::builtin_variables(&target.dir);
+    // Each tool builds with its own backend; hatchling is the template's default.
+    let build_system = match backend {
+        "poetry" => Some(("poetry-core>=2.0", "poetry.core.masonry.api")),
+        "pdm" => Some(("pdm-backend", "pdm.backend")),
+        _ => None,
+    };
+    if let Some((requires, build_backend)) = build_system {
+        vars.insert("build_requires".to_string(), requires.to_string());
+        vars.insert("build_backend".to_string(), build_backend.to_string());
     }
-}
+    // A .gitignore only makes sense in a git repository.
+    let gitignore = if git { opts.gitignore } else { Some(false) };
+    if let Some(gitignore) = gitignore {
+        vars.insert("gitignore".to_string(), gitignore.to_string());
+    }
+    let vars = template.resolve_variables(vars, opts, prompter)?;
 
-fn is_python_installed() -> bool {
-    if cfg!(target_os = "windows") {
-        if let Ok(o) = std::process::Command::new("where").arg("python").output() {
-            if o.status.success() && !String::from_utf8_lossy(&o.stdout).is_empty() {
-                return true;
-            }
-        }
-        if let Ok(o) = std::process::Command::new("where").arg("python3").output() {
-            if o.status.success() && !String::from_utf8_lossy(&o.stdout).is_empty() {
-                return true;
-            }
+    let install = opts.confirm(
+        prompter,
+        opts.venv,
+        "Create a virtual environment and install the dev dependencies? (y/n)",
+        true,
+    )?;
+    // pip has no lockfile of its own, so the installed versions can be pinned instead.
+    let pin = backend == "pip"
+        && install
+        && opts.confirm(
+            prompter,
+            opts.requirements,
+            "Pin the installed packages in requirements.txt? (y/n)",
+            true,
+        )?;
+
+    let mut plan = Plan::new(&target);
+    if git {
+        plan.command("git", &["init"]);
+    }
+    plan.render(template, vars)?;
+    // Installs and lockfiles are optional steps: they need the network and are easy to rerun.
+    if backend == "pip" {
+        if install {
+            add_pip_steps(&mut plan, &target.dir, pin);
         }
-        if let Ok(local_appdata) = std::env::var("LOCALAPPDATA") {
-            let path = std::path::Path::new(&local_appdata)
-                .join("Microsoft")
-                .join("WindowsApps")
-                .join("python.exe");
-            if path.exists() {
-                return true;
-            }
+    } else if let Some(args) = environment_args(backend, install) {
+        if probe::is_installed(backend) {
+            plan.optional_command(backend, args);
+        } else {
+            println!(
+                "{} is not installed, skipping the environment and lockfile. Run `{} {}` inside the project once it is.",
+                backend,
+                backend,
+                args.join(" ")
+            );
         }
-        false
-    } else if cfg!(target_os = "linux") {
-        std::path::Path::new("/usr/bin/python3").exists()
-    } else if cfg!(target_os = "macos") {
-        unimplemented!("Python installation check for macOS is not implemented yet.");
-    } else {
-        eprintln!("Unsupported OS. Please install Python manually.");
-        false
     }
+    plan.execute(ProgrammingLanguage::Python, opts, prompter)
 }
 
-fn install_python() -> color_eyre::Result<()> {
-    println!("Python is not installed. Installing Python...");
-    if cfg!(target_os = "windows") {
-        // TODO: Find a better way to get the latest version of Python
-        let installer_link = "https://www.python.org/ftp/python/3.13.2/python-3.13.2-amd64.exe";
-
-        let temp_path = temp_dir().join("python-3.13.2-amd64.exe");
-        let temp_path_str = temp_path
-            .to_str()
-            .expect("Failed to convert path to string");
-
-        let output = Command::new("curl")
-            .arg("-o")
-            .arg(temp_path_str)
-            .arg(installer_link)
-            .output()
-            .expect("Failed to download Python installer");
-        if !output.status.success() {
-            eprintln!(
-                "Failed to download Python installer: {}",
-                String::from_utf8_lossy(&output.stderr)
+/// The command that sets up the environment, or just the lockfile when not installing.
+/// `uv sync`, `poetry install` and `pdm install` lock as part of installing.
+fn environment_args(backend: &str, install: bool) -> Option<&'static [&'static str]> {
+    match (backend, install) {
+        ("uv", true) => Some(&["sync", "--extra", "dev"]),
+        ("poetry", true) => Some(&["install", "--all-extras"]),
+        ("pdm", true) => Some(&["install", "-G", "dev"]),
+        ("hatch", true) => Some(&["env", "create"]),
+        ("hatch", false) => {
+            println!(
+                "Hatch doesn't write lockfiles; run `hatch env create` to set up the environment."
             );
-            Ok(())
-        } else {
-            let output = Command::new(temp_path_str)
-                .output()
-                .expect("Failed to run Python installer");
-
-            if !output.status.success() {
-                eprintln!(
-                    "Failed to run Python installer: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                );
-            } else {
-                println!("Python installed successfully. Please relaunch the terminal.");
-                if yn::ask("Would you like to remove the installer file? (y/n)").unwrap_or(false) {
-                    fs::remove_file(temp_path_str).expect("Failed to remove Python installer");
-                }
-            }
-            Ok(())
+            None
         }
-    } else if cfg!(target_os = "linux") {
-        println!("Please install Python using your package manager.");
+        ("pdm", false) => Some(&["lock", "-G", "dev"]),
+        _ => Some(&["lock"]),
+    }
+}
 
-        let output = Command::new("uname")
-            .arg("-a")
-            .output()
-            .expect("Failed to get system information");
-        if output.status.success() {
-            let os_info = String::from_utf8_lossy(&output.stdout);
-            match os_info.as_ref() {
-                info if info.contains("Ubuntu") => {
-                    println!("Installing Python on Ubuntu...");
-                    Command::new("sudo")
-                        .arg("apt-get")
-                        .arg("install")
-                        .arg("python3")
-                        .status()
-                        .expect("Failed to install Python on Ubuntu");
-                }
-                info if info.contains("Fedora") => {
-                    println!("Installing Python on Fedora...");
-                    Command::new("sudo")
-                        .arg("dnf")
-                        .arg("install")
-                        .arg("python3")
-                        .status()
-                        .expect("Failed to install Python on Fedora");
-                }
-                info if info.contains("Arch") => {
-                    println!("Installing Python on Arch...");
-                    Command::new("sudo")
-                        .arg("pacman")
-                        .arg("-S")
-                        .arg("python")
-                        .status()
-                        .expect("Failed to install Python on Arch");
+/// Creates `.venv`, installs the project with its dev extras, and optionally pins the result in
+/// requirements.txt.
+fn add_pip_steps(plan: &mut Plan, project_dir: &Path, pin: bool) {
+    let python_cmd = if cfg!(target_os = "windows") {
+        "python"
+    } else {
+        "python3"
+    };
+    let pip = project_dir.join(if cfg!(target_os = "windows") {
+        ".venv/Scripts/pip"
+    } else {
+        ".venv/bin/pip"
+    });
+    let pip = pip.to_string_lossy().into_owned();
+    plan.optional_command(python_cmd, &["-m", "venv", ".venv", "--upgrade-deps"])
+        .optional_command(&pip, &["install", "-e", ".[dev]"]);
+    if pin {
+        plan.optional_step(
+            "Pin the installed packages in requirements.txt",
+            move |dir| {
+                let output = Command::new(&pip)
+                    .args(["freeze", "--exclude-editable"])
+                    .current_dir(dir)
+                    .output()?;
+                if !output.status.success() {
+                    bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
                 }
-                _ => println!("Unsupported Linux distribution. Please install Python manually."),
-            }
-            Ok(())
-        } else {
-            eprintln!(
-                "Failed to get system information: {}",
-                String::from_utf8_lossy(&output.stderr)
-            );
-            Ok(())
-        }
-    } else if cfg!(target_os = "macos") {
-        println!("Please install Python using Homebrew: brew install python");
-        Ok(())
+                fs::write(dir.join("requirements.txt"), output.stdout)?;
+                Ok(())
+            },
+        );
+    }
+}
+
+fn install_python(opts: &ScaffoldOptions, prompter: &mut dyn Prompter) -> color_eyre::Result<()> {
+    let installed = if cfg!(target_os = "windows") {
+        println!("Python is not installed.");
+        let plan = windows_installer_plan()?;
+        install::confirm_and_run(&plan, opts, prompter)?
     } else {
-        println!("Unsupported OS. Please install Python manually.");
-        Ok(())
+        install::offer(Tool::Python3, opts, prompter)?
+    };
+    if installed {
+        println!("Python installed successfully. Please relaunch the terminal.");
     }
+    Ok(())
+}
+
+/// Plans running the python.org installer for the latest Python 3 release, as listed by the
+/// python.org downloads API along with the installer's checksum.
+fn windows_installer_plan() -> color_eyre::Result<InstallPlan> {
+    const API: &str = "https://www.python.org/api/v2/downloads";
+    let downloader = Downloader::new()?;
+    let releases: Value = serde_json::from_str(&downloader.text(&format!(
+        "{}/release/?is_published=true&pre_release=false&version=3",
+        API
+    ))?)?;
+    let release = releases
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|release| release["is_latest"].as_bool() == Some(true))
+        .ok_or_else(|| eyre!("python.org lists no latest Python 3 release"))?;
+    let version = release["name"]
+        .as_str()
+        .unwrap_or_default()
+        .trim_start_matches("Python ")
+        .to_string();
+    // The release's id is the last segment of its resource URI.
+    let id = release["resource_uri"]
+        .as_str()
+        .and_then(|uri| uri.trim_end_matches('/').rsplit('/').next())
+        .ok_or_else(|| eyre!("Unexpected python.org release listing"))?;
+    let files: Value =
+        serde_json::from_str(&downloader.text(&format!("{}/release_file/?release={}", API, id))?)?;
+    let suffix = if cfg!(target_arch = "aarch64") {
+        "-arm64.exe"
+    } else {
+        "-amd64.exe"
+    };
+    let installer = files
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|file| {
+            file["url"]
+                .as_str()
+                .is_some_and(|url| url.ends_with(suffix))
+        })
+        .ok_or_else(|| eyre!("python.org has no Windows installer for Python {}", version))?;
+    let url = installer["url"].as_str().unwrap_or_default();
+    let file_name = url.rsplit('/').next().unwrap_or("python-installer.exe");
+    let sha256 = installer["sha256_sum"]
+        .as_str()
+        .filter(|sum| !sum.is_empty());
+
+    let mut plan = InstallPlan::new("python3", format!("Python {}", version));
+    let installer = plan.download_verified(url, file_name, sha256)?;
+    plan.run(&[&installer.to_string_lossy()], false);
+    Ok(plan)
 }
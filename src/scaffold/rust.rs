@@ -1,186 +1,330 @@
 use std::env;
-use std::fs;
 use std::path::Path;
-use std::process::Command;
 
-use crate::database::model::Project;
+use color_eyre::eyre::{bail, eyre};
+
+use crate::install::{self, download::Downloader, plan::InstallPlan};
+use crate::probe;
+use crate::scaffold::{self, plan::Plan, ScaffoldOptions};
 use crate::state::languages::ProgrammingLanguage;
-use crate::ui::{grab_string::ask, yn};
-
-pub fn setup(project_name: Option<&str>) {
-    if !is_tool_installed("rustc") || !is_tool_installed("cargo") {
-        // Ask if they'd like to install Rust
-        let choice = yn::ask("Rust is not installed. Would you like to install it? (y/n)")
-            .expect("Failed to get user input");
-
-        if choice {
-            if cfg!(target_os = "windows") {
-                let temp_dir = env::temp_dir().join("rustup_install");
-                if temp_dir.exists() {
-                    fs::remove_dir_all(&temp_dir)
-                        .expect("Failed to remove existing temp directory");
-                }
-                fs::create_dir_all(&temp_dir).expect("Failed to create temp directory");
-
-                let rustup_path = temp_dir.join("rustup-init.exe");
-                let rustup_url = if cfg!(target_arch = "aarch64") {
-                    "https://win.rustup.rs/aarch64"
-                } else {
-                    "https://win.rustup.rs/x86_64"
-                };
-                Command::new("curl")
-                    .args(&["-o", rustup_path.to_str().unwrap(), rustup_url])
-                    .status()
-                    .expect("Failed to download rustup-init.exe");
-
-                Command::new(rustup_path)
-                    .status()
-                    .expect("Failed to execute rustup-init.exe");
-
-                fs::remove_dir_all(&temp_dir).expect("Failed to remove temp directory");
-            } else {
-                Command::new("sh")
-                    .arg("-c")
-                    .arg("curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh")
-                    .status()
-                    .expect("Failed to execute command to install Rust");
-            }
-            println!("Note: You will have to run the projector setup again due to new environment files. Please restart your terminal.");
-        } else {
-            println!("Exiting as Rust is not installed.");
-        }
-        return;
-    }
-
-    match project_name {
-        Some(name) => {
-            if name.trim().is_empty() {
-                println!("Exiting as no project name was provided.");
-                return;
-            }
-            if name == "." {
-                let status = Command::new("cargo")
-                    .arg("init")
-                    .status()
-                    .expect("Failed to execute cargo init");
-                if !status.success() {
-                    eprintln!("Failed to run cargo init in current directory");
-                } else {
-                    println!("Initialized current directory as a Rust project");
-                    let project_path = env::current_dir()
-                        .expect("Failed to get current directory")
-                        .canonicalize()
-                        .expect("Failed to get full path");
-                    let project_name = project_path
-                        .file_name()
-                        .expect("Failed to get project name");
-                    let mut project = Project::new(
-                        ProgrammingLanguage::Rust,
-                        project_name.to_str().unwrap().to_string(),
-                        &project_path,
-                    );
-                    if let Err(e) = crate::post_setup::run_post_setup(&mut project) {
-                        eprintln!("Post setup failed: {}", e);
-                    }
-                }
-            } else {
-                let output = Command::new("cargo")
-                    .arg("new")
-                    .arg(name)
-                    .output()
-                    .expect("Failed to execute cargo new");
-
-                if !output.status.success() {
-                    eprintln!("Error: {}", String::from_utf8_lossy(&output.stderr));
-                } else {
-                    println!("Project {} created successfully", name);
-                    let project_path = Path::new(name)
-                        .canonicalize()
-                        .expect("Failed to get full path");
-                    let project_name = project_path.file_name().unwrap().to_str().unwrap();
-                    let mut project = Project::new(
-                        ProgrammingLanguage::Rust,
-                        project_name.to_string(),
-                        project_path.as_path(),
-                    );
-                    if let Err(e) = crate::post_setup::run_post_setup(&mut project) {
-                        eprintln!("Post setup failed: {}", e);
-                    }
-                }
-            }
+use crate::template::{self, render::Variables, Template};
+use crate::ui::prompt::Prompter;
+
+const KINDS: [&str; 3] = ["bin", "lib", "workspace"];
+const EDITIONS: [&str; 3] = ["2024", "2021", "2018"];
+
+pub fn setup(
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    if !probe::is_installed("rustc") || !probe::is_installed("cargo") {
+        return install_rust(opts, prompter);
+    }
+    scaffold(project_name, opts, prompter)
+}
+
+fn scaffold(
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter
+            .input("Project name? (leave blank to use current directory)")?
+            .trim()
+            .to_string(),
+    };
+
+    let kind = opts.choose(
+        prompter,
+        opts.kind.as_deref(),
+        "What kind of project is this?",
+        &KINDS,
+    )?;
+    let edition = opts.choose(
+        prompter,
+        opts.edition.as_deref(),
+        "Which Rust edition should the project use?",
+        &EDITIONS,
+    )?;
+    // `none` comes first so it's the default; workspaces only get presets through their members.
+    let presets: &[&str] = match kind {
+        "bin" => &["none", "cli", "service"],
+        "lib" => &["none", "bench"],
+        _ => &["none"],
+    };
+    let preset = opts.choose(
+        prompter,
+        opts.preset.as_deref(),
+        "Start from a preset?",
+        presets,
+    )?;
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
+    let toolchain_files = opts.confirm(
+        prompter,
+        opts.toolchain_files,
+        "Add rust-toolchain.toml, rustfmt.toml and clippy.toml? (y/n)",
+        true,
+    )?;
+
+    let target = scaffold::target(ProgrammingLanguage::Rust, &name, opts, prompter)?;
+    let mut plan = Plan::new(&target);
+    let mut vars = template::builtin_variables(&target.dir);
+    vars.insert("edition".to_string(), edition.to_string());
+    vars.insert("msrv".to_string(), msrv(edition).to_string());
+    if kind == "workspace" {
+        plan_workspace(&mut plan, &target.dir, edition, git, opts, prompter)?;
+    } else if preset == "none" {
+        let mut args = vec![
+            "init",
+            "--edition",
+            edition,
+            "--vcs",
+            if git { "git" } else { "none" },
+        ];
+        if kind == "lib" {
+            args.push("--lib");
         }
-        None => {
-            let answer = ask("Project name? (use '.' to use current directory)")
-                .expect("Failed to get project name");
-
-            if answer.trim().is_empty() {
-                println!("Exiting as no project name was provided.");
-                return;
-            }
-
-            if answer.trim() == "." {
-                let status = Command::new("cargo")
-                    .arg("init")
-                    .status()
-                    .expect("Failed to execute cargo init");
-                if !status.success() {
-                    eprintln!("Failed to run cargo init in current directory");
-                } else {
-                    println!("Initialized current directory as a Rust project");
-                    let project_path = env::current_dir()
-                        .expect("Failed to get current directory")
-                        .canonicalize()
-                        .expect("Failed to get full path");
-                    let project_name = project_path
-                        .file_name()
-                        .expect("Failed to get project name")
-                        .to_str()
-                        .expect("Failed to convert project name to string");
-                    let mut project = Project::new(
-                        ProgrammingLanguage::Rust,
-                        project_name.to_string(),
-                        &project_path,
-                    );
-                    if let Err(e) = crate::post_setup::run_post_setup(&mut project) {
-                        eprintln!("Post setup failed: {}", e);
-                    }
-                }
-            } else {
-                let output = Command::new("cargo")
-                    .arg("new")
-                    .arg(answer.trim())
-                    .output()
-                    .expect("Failed to execute cargo new");
-
-                if !output.status.success() {
-                    eprintln!("Error: {}", String::from_utf8_lossy(&output.stderr));
-                } else {
-                    println!("Project {} created successfully", answer.trim());
-                    let project_path = Path::new(answer.trim())
-                        .canonicalize()
-                        .expect("Failed to get full path");
-                    let project_name = project_path.file_name().unwrap().to_str().unwrap();
-                    let mut project = Project::new(
-                        ProgrammingLanguage::Rust,
-                        project_name.to_string(),
-                        project_path.as_path(),
-                    );
-                    if let Err(e) = crate::post_setup::run_post_setup(&mut project) {
-                        eprintln!("Post setup failed: {}", e);
-                    }
-                }
-            }
+        plan.command("cargo", &args);
+    } else {
+        let (template, vars) = load(&format!("rust-{}", preset), &vars, opts, prompter)?;
+        plan_preset(&mut plan, &target.dir, template, vars, git)?;
+    }
+    if toolchain_files {
+        let (template, vars) = load("rust-tooling", &vars, opts, prompter)?;
+        plan.render(template, vars)?;
+    }
+    plan.execute(ProgrammingLanguage::Rust, opts, prompter)
+}
+
+/// Adds the steps creating a virtual workspace with its member crates under `crates/`. Members
+/// are given as `name` or `name:lib`/`name:bin`, defaulting to a binary.
+fn plan_workspace(
+    plan: &mut Plan,
+    project_dir: &Path,
+    edition: &str,
+    git: bool,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    if project_dir.join("Cargo.toml").exists() {
+        bail!("{} already contains a Cargo.toml", project_dir.display());
+    }
+    let dir_name = project_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| eyre!("Failed to get project name"))?;
+
+    let members: Vec<String> = if opts.members.is_empty() {
+        let default = format!("{0}:bin,{0}-core:lib", dir_name);
+        let answer = if opts.assume_yes {
+            String::new()
+        } else {
+            prompter.input(&format!(
+                "Member crates? (comma separated, name:bin or name:lib) [{}]",
+                default
+            ))?
+        };
+        let answer = if answer.trim().is_empty() {
+            default
+        } else {
+            answer
+        };
+        answer
+            .split(',')
+            .map(|member| member.trim().to_string())
+            .filter(|member| !member.is_empty())
+            .collect()
+    } else {
+        opts.members.clone()
+    };
+
+    // Edition 2024 defaults to resolver 3; keep members on the same one.
+    let resolver = if edition == "2024" { "3" } else { "2" };
+    plan.write(
+        "Cargo.toml",
+        format!(
+            "[workspace]\nresolver = \"{}\"\nmembers = [\"crates/*\"]\n\n[workspace.package]\nedition = \"{}\"\n",
+            resolver, edition
+        ),
+    );
+    for member in &members {
+        let (member, kind) = member.split_once(':').unwrap_or((member, "bin"));
+        let path = format!("crates/{}", member);
+        let mut args = vec!["new", "--vcs", "none", "--edition", edition, &path];
+        match kind {
+            "bin" => {}
+            "lib" => args.push("--lib"),
+            _ => bail!("Member {} must be a bin or a lib, not '{}'", member, kind),
         }
+        plan.command("cargo", &args);
+    }
+    if git {
+        plan_git(plan);
+    }
+    Ok(())
+}
+
+/// Adds the steps creating a crate from a preset template. The preset brings its own Cargo.toml
+/// with its dependencies, so it's rendered instead of running `cargo init`, which would write a
+/// bare one first.
+fn plan_preset(
+    plan: &mut Plan,
+    project_dir: &Path,
+    template: Template,
+    vars: Variables,
+    git: bool,
+) -> color_eyre::Result<()> {
+    if project_dir.join("Cargo.toml").exists() {
+        bail!("{} already contains a Cargo.toml", project_dir.display());
+    }
+    plan.render(template, vars)?;
+    if git {
+        plan_git(plan);
+    }
+    Ok(())
+}
+
+/// Sets up Git the way `cargo init --vcs git` does.
+fn plan_git(plan: &mut Plan) {
+    plan.command("git", &["init"])
+        .write(".gitignore", "/target\n");
+}
+
+/// Finds a template and resolves its variables on top of `vars`.
+fn load(
+    template_name: &str,
+    vars: &Variables,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<(Template, Variables)> {
+    let template = template::find(template_name)?
+        .ok_or_else(|| eyre!("No {} template found", template_name))?;
+    let vars = template.resolve_variables(vars.clone(), opts, prompter)?;
+    Ok((template, vars))
+}
+
+/// The oldest Rust release that supports `edition`.
+fn msrv(edition: &str) -> &'static str {
+    match edition {
+        "2018" => "1.31",
+        "2021" => "1.56",
+        _ => "1.85",
     }
 }
 
-fn is_tool_installed(tool: &str) -> bool {
-    if env::var("FAKE_UNINSTALLED").is_ok() {
-        return false;
+fn install_rust(opts: &ScaffoldOptions, prompter: &mut dyn Prompter) -> color_eyre::Result<()> {
+    println!("Rust is not installed.");
+    let mut plan = InstallPlan::new("rust", "Rust with rustup");
+    let file_name = if cfg!(target_os = "windows") {
+        "rustup-init.exe"
+    } else {
+        "rustup-init"
+    };
+    let url = format!(
+        "https://static.rust-lang.org/rustup/dist/{}/{}",
+        rustup_target()?,
+        file_name
+    );
+    let sha256 = Downloader::new()?.published_sha256(&format!("{}.sha256", url), file_name)?;
+    let rustup_init = plan.download_verified(&url, file_name, Some(&sha256))?;
+    let rustup_init = rustup_init.to_string_lossy();
+    if !cfg!(target_os = "windows") {
+        plan.run(&["chmod", "+x", &rustup_init], false);
+    }
+    plan.run(&[&rustup_init], false);
+
+    if install::confirm_and_run(&plan, opts, prompter)? {
+        println!("Note: You will have to run the projector setup again due to new environment files. Please restart your terminal.");
+    } else if !opts.dry_run {
+        println!("Exiting as Rust is not installed.");
+    }
+    Ok(())
+}
+
+/// The target rustup-init is published for on this machine.
+fn rustup_target() -> color_eyre::Result<String> {
+    let arch = env::consts::ARCH;
+    match env::consts::OS {
+        "linux" if cfg!(target_env = "musl") => Ok(format!("{}-unknown-linux-musl", arch)),
+        "linux" => Ok(format!("{}-unknown-linux-gnu", arch)),
+        "macos" => Ok(format!("{}-apple-darwin", arch)),
+        "windows" => Ok(format!("{}-pc-windows-msvc", arch)),
+        os => Err(eyre!(
+            "rustup isn't published for {}; see https://rustup.rs",
+            os
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::scaffold::Target;
+    use crate::template::render;
+
+    fn preset(name: &str) -> Template {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/template/builtin")
+            .join(name);
+        Template::from_dir(&dir).unwrap()
+    }
+
+    fn scaffold_preset(name: &str, dir: &Path, merge: bool) -> color_eyre::Result<bool> {
+        let mut vars = render::name_variables("app");
+        vars.insert("edition".to_string(), "2021".to_string());
+        let mut plan = Plan::new(&Target {
+            dir: dir.to_path_buf(),
+            merge,
+        });
+        plan_preset(&mut plan, dir, preset(name), vars, false)?;
+        plan.run(ProgrammingLanguage::Rust, &[], false)
+    }
+
+    #[test]
+    fn presets_keep_their_dependencies() {
+        let temp = tempfile::tempdir().unwrap();
+        let cases = [
+            ("rust-cli", "src/main.rs", "clap = "),
+            ("rust-service", "src/main.rs", "tokio = "),
+            ("rust-bench", "benches/benchmarks.rs", "[[bench]]"),
+        ];
+        for (name, source, expected) in cases {
+            let dir = temp.path().join(name);
+            assert!(scaffold_preset(name, &dir, false).unwrap());
+            let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+            assert!(manifest.contains(expected), "{}: {}", name, manifest);
+            assert!(manifest.contains("name = \"app\""), "{}", manifest);
+            assert!(manifest.contains("edition = \"2021\""), "{}", manifest);
+            assert!(dir.join(source).is_file(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn presets_keep_their_dependencies_in_the_current_directory() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(temp.path().join("notes.txt"), "kept\n").unwrap();
+        assert!(scaffold_preset("rust-cli", temp.path(), true).unwrap());
+        let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+        assert!(manifest.contains("clap = "), "{}", manifest);
+        assert!(temp.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn presets_refuse_an_existing_crate() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(temp.path().join("Cargo.toml"), "[package]\n").unwrap();
+        let error = scaffold_preset("rust-cli", temp.path(), true).unwrap_err();
+        assert!(error.to_string().contains("already contains a Cargo.toml"));
     }
-    Command::new(tool)
-        .arg("--version")
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
 }
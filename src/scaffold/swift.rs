@@ -0,0 +1,136 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::eyre;
+use directories::BaseDirs;
+
+use crate::probe;
+use crate::scaffold::{self, plan::Plan, ScaffoldOptions};
+use crate::state::languages::ProgrammingLanguage;
+use crate::ui::prompt::Prompter;
+
+/// `swift package init --type` values offered.
+const KINDS: [&str; 2] = ["executable", "library"];
+
+pub fn setup(
+    project_name: Option<&str>,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let Some(swift) = find_swift() else {
+        println!("Swift is not installed. Install it with swiftly (https://www.swift.org/install/linux), then run projector again.");
+        return Ok(());
+    };
+
+    let kind = opts.choose(
+        prompter,
+        opts.kind.as_deref(),
+        "What kind of package is this?",
+        &KINDS,
+    )?;
+
+    let name = match project_name {
+        Some(name) => name.trim().to_string(),
+        None if opts.assume_yes => String::new(),
+        None => prompter.input("Project name? (leave blank to use current directory)")?,
+    };
+    let target = scaffold::target(ProgrammingLanguage::Swift, &name, opts, prompter)?;
+    let dir_name = target
+        .dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| eyre!("Failed to get project name"))?;
+    let git = opts.confirm(
+        prompter,
+        opts.git,
+        "Set up Git for this project? (y/n)",
+        true,
+    )?;
+
+    let mut plan = Plan::new(&target);
+    plan.command(
+        &swift.to_string_lossy(),
+        &["package", "init", "--type", kind, "--name", &dir_name],
+    );
+    // Libraries come with a test target already; executables don't.
+    if kind == "executable" {
+        plan.step(
+            format!("Add the {}Tests test target", dir_name),
+            move |dir| add_test_target(dir, &dir_name),
+        );
+    }
+    plan.step("Write .gitignore unless SwiftPM did", |dir| {
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            fs::write(
+                gitignore,
+                ".DS_Store\n/.build\n/Packages\n.swiftpm/\n/*.xcodeproj\nPackage.resolved\n",
+            )?;
+        }
+        Ok(())
+    });
+    if git {
+        plan.command("git", &["init"]);
+    }
+    plan.execute(ProgrammingLanguage::Swift, opts, prompter)
+}
+
+/// Finds the `swift` binary, falling back to the default swiftly and swiftenv install locations
+/// for when their environment hasn't been sourced in this shell yet.
+fn find_swift() -> Option<PathBuf> {
+    if probe::is_installed("swift") {
+        return Some(PathBuf::from("swift"));
+    }
+    let base_dirs = BaseDirs::new()?;
+    let swiftly_bin = env::var_os("SWIFTLY_BIN_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| base_dirs.data_dir().join("swiftly/bin"));
+    let swiftenv_root = env::var_os("SWIFTENV_ROOT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| base_dirs.home_dir().join(".swiftenv"));
+    let swift = [swiftly_bin, swiftenv_root.join("shims")]
+        .into_iter()
+        .map(|dir| dir.join("swift"))
+        .find(|swift| probe::is_installed(&swift.to_string_lossy()))?;
+    println!(
+        "Using {}. Add its directory to your PATH to use it outside projector.",
+        swift.display()
+    );
+    Some(swift)
+}
+
+/// Adds `Tests/<name>Tests` with an XCTest case importing the executable target, and declares
+/// it in Package.swift.
+fn add_test_target(project_dir: &Path, name: &str) -> color_eyre::Result<()> {
+    let module: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let test_target = format!("{}Tests", name);
+    let manifest_path = project_dir.join("Package.swift");
+    let manifest = fs::read_to_string(&manifest_path)?;
+    let Some(index) = manifest.find("targets: [") else {
+        eprintln!("Warning: couldn't find the targets in Package.swift, skipping the test target");
+        return Ok(());
+    };
+    let index = index + "targets: [".len();
+    let manifest = format!(
+        "{}\n        .testTarget(name: \"{}\", dependencies: [\"{}\"]),{}",
+        &manifest[..index],
+        test_target,
+        name,
+        &manifest[index..]
+    );
+    fs::write(&manifest_path, manifest)?;
+
+    let tests_dir = project_dir.join("Tests").join(&test_target);
+    fs::create_dir_all(&tests_dir)?;
+    fs::write(
+        tests_dir.join(format!("{}.swift", test_target)),
+        format!(
+            "import XCTest\n@testable import {module}\n\nfinal class {module}Tests: XCTestCase {{\n    func testExample() throws {{\n        XCTAssertEqual(1 + 1, 2)\n    }}\n}}\n"
+        ),
+    )?;
+    Ok(())
+}
@@ -1,13 +1,47 @@
+use std::collections::BTreeMap;
+
+use crate::install::InstallMode;
 use crate::post_setup;
+use crate::post_setup::hooks::Hooks;
 
 use super::languages::ProgrammingLanguage;
 use ratatui::widgets::ListState;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub editor: post_setup::editor::ValidEditors,
     pub open_editor_after_setup: bool,
+    /// Author name for templates. Falls back to git's `user.name` when unset.
+    pub author: Option<String>,
+    /// License identifier for templates, e.g. `MIT`.
+    pub license: String,
+    /// Where new projects will be hosted, e.g. `github.com/alice`. Used to build default Go
+    /// module paths and similar identifiers.
+    pub forge_prefix: Option<String>,
+    /// Whether projector may run installs needing root or administrator rights, like
+    /// `sudo apt-get install`. When off, it prints what to run instead.
+    pub allow_privileged_installs: bool,
+    /// How missing tools get installed: `system` with the package manager, or `local` from
+    /// official builds into projector's own prefix, which needs no root.
+    pub install_mode: InstallMode,
+    /// URL prefixes to download from a mirror instead, e.g.
+    /// `"https://go.dev/dl" = "https://mirror.example.com/go"`. Applies to installers, toolchain
+    /// archives and the indexes listing them.
+    pub download_mirrors: BTreeMap<String, String>,
+    /// Proxy for every download, e.g. `http://proxy.example.com:3128`. Without it, the
+    /// `HTTPS_PROXY` and `HTTP_PROXY` environment variables are used.
+    pub download_proxy: Option<String>,
+    /// Oldest tool versions to scaffold with, per language, e.g. `[min_versions.rust]` with
+    /// `rustc = "1.80"`.
+    pub min_versions: BTreeMap<String, BTreeMap<String, String>>,
+    /// Commands run for every project before and after scaffolding and opening it, e.g.
+    /// `[[hooks.post_scaffold]]` with `command = "pre-commit install"`.
+    pub hooks: Hooks,
+    /// Hooks for one language's projects, run after the global ones, e.g.
+    /// `[[language_hooks.python.post_scaffold]]`.
+    pub language_hooks: BTreeMap<String, Hooks>,
 }
 
 impl Default for Config {
@@ -15,6 +49,16 @@ impl Default for Config {
         Config {
             editor: post_setup::editor::ValidEditors::Code,
             open_editor_after_setup: false,
+            author: None,
+            license: "MIT".to_string(),
+            forge_prefix: None,
+            allow_privileged_installs: true,
+            install_mode: InstallMode::System,
+            download_mirrors: BTreeMap::new(),
+            download_proxy: None,
+            min_versions: BTreeMap::new(),
+            hooks: Hooks::default(),
+            language_hooks: BTreeMap::new(),
         }
     }
 }
@@ -38,6 +82,8 @@ impl App {
             settings: vec![
                 Setting::new("Editor", vec!["VSCode", "IntelliJ"]),
                 Setting::new("Open After Setup", vec!["true", "false"]),
+                Setting::new("Privileged Installs", vec!["allow", "forbid"]),
+                Setting::new("Install Mode", vec!["system", "local"]),
             ],
             selected_setting_index: 0,
         }
@@ -50,6 +96,11 @@ impl App {
             post_setup::editor::ValidEditors::Intellij => 1,
         };
         self.settings[1].selected_option_index = if cfg.open_editor_after_setup { 1 } else { 0 };
+        self.settings[2].selected_option_index = if cfg.allow_privileged_installs { 0 } else { 1 };
+        self.settings[3].selected_option_index = match cfg.install_mode {
+            InstallMode::System => 0,
+            InstallMode::Local => 1,
+        };
         Ok(())
     }
 
@@ -59,9 +110,14 @@ impl App {
             "intellij" => post_setup::editor::ValidEditors::Intellij,
             _ => post_setup::editor::ValidEditors::Code,
         };
-        let cfg = Config {
-            editor,
-            open_editor_after_setup: self.settings[1].value() == "true",
+        // Keep settings that aren't editable from this screen.
+        let mut cfg: Config = confy::load("projector", None)?;
+        cfg.editor = editor;
+        cfg.open_editor_after_setup = self.settings[1].value() == "true";
+        cfg.allow_privileged_installs = self.settings[2].value() == "allow";
+        cfg.install_mode = match self.settings[3].value() {
+            "local" => InstallMode::Local,
+            _ => InstallMode::System,
         };
         confy::store("projector", None, cfg)?;
         Ok(())
@@ -71,7 +127,7 @@ impl App {
     pub fn next_item(&mut self) {
         let all_langs = ProgrammingLanguage::all_langs();
         self.selected_lang_index = (self.selected_lang_index + 1) % all_langs.len();
-        self.selected_lang = Some(all_langs[self.selected_lang_index].clone());
+        self.selected_lang = Some(all_langs[self.selected_lang_index]);
         self.list_state.select(Some(self.selected_lang_index));
     }
 
@@ -83,7 +139,7 @@ impl App {
         } else {
             self.selected_lang_index -= 1;
         }
-        self.selected_lang = Some(all_langs[self.selected_lang_index].clone());
+        self.selected_lang = Some(all_langs[self.selected_lang_index]);
         self.list_state.select(Some(self.selected_lang_index));
     }
 
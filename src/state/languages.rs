@@ -32,10 +32,27 @@ impl ProgrammingLanguage {
             ProgrammingLanguage::Kotlin,
         ]
     }
+
+    /// Looks up a language by the name typed on the command line, e.g. `rust`, `py` or `c++`.
+    pub fn from_name(name: &str) -> Option<ProgrammingLanguage> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Some(ProgrammingLanguage::Rust),
+            "python" | "py" => Some(ProgrammingLanguage::Python),
+            "javascript" | "js" => Some(ProgrammingLanguage::JavaScript),
+            "java" => Some(ProgrammingLanguage::Java),
+            "c" => Some(ProgrammingLanguage::C),
+            "csharp" | "c#" | "cs" => Some(ProgrammingLanguage::CSharp),
+            "cplusplus" | "c++" | "cpp" => Some(ProgrammingLanguage::CPlusPlus),
+            "go" | "golang" => Some(ProgrammingLanguage::Go),
+            "swift" => Some(ProgrammingLanguage::Swift),
+            "kotlin" | "kt" => Some(ProgrammingLanguage::Kotlin),
+            _ => None,
+        }
+    }
 }
 
 impl ToSql for ProgrammingLanguage {
-    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput> {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
         Ok(ToSqlOutput::from(match self {
             ProgrammingLanguage::Rust => "Rust",
             ProgrammingLanguage::Python => "Python",
@@ -0,0 +1,15 @@
+use axum::{routing::get, Router};
+
+async fn index() -> &'static str {
+    "Hello from {{ project_name }}!"
+}
+
+#[tokio::main]
+async fn main() {
+    let app = Router::new().route("/", get(index));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+        .await
+        .expect("failed to bind to port 3000");
+    println!("Listening on http://127.0.0.1:3000");
+    axum::serve(listener, app).await.expect("server error");
+}
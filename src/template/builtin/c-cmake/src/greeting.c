@@ -0,0 +1,5 @@
+#include "{{ crate_name }}/greeting.h"
+
+const char *greeting(void) {
+    return "Hello from {{ project_name }}!";
+}
@@ -0,0 +1,8 @@
+#include <stdio.h>
+
+#include "{{ crate_name }}/greeting.h"
+
+int main(void) {
+    puts(greeting());
+    return 0;
+}
@@ -0,0 +1,7 @@
+#ifndef {{ crate_name | upper }}_GREETING_H
+#define {{ crate_name | upper }}_GREETING_H
+
+/* Returns the greeting printed by the program. */
+const char *greeting(void);
+
+#endif
@@ -0,0 +1,13 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "{{ crate_name }}/greeting.h"
+
+int main(void) {
+    const char *expected = "Hello from {{ project_name }}!";
+    if (strcmp(greeting(), expected) != 0) {
+        fprintf(stderr, "expected \"%s\", got \"%s\"\n", expected, greeting());
+        return 1;
+    }
+    return 0;
+}
@@ -0,0 +1,10 @@
+#pragma once
+
+#include <string>
+
+namespace {{ crate_name }} {
+
+// Returns the greeting printed by the program.
+std::string greeting();
+
+}  // namespace {{ crate_name }}
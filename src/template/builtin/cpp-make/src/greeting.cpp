@@ -0,0 +1,9 @@
+#include "{{ crate_name }}/greeting.hpp"
+
+namespace {{ crate_name }} {
+
+std::string greeting() {
+    return "Hello from {{ project_name }}!";
+}
+
+}  // namespace {{ crate_name }}
@@ -0,0 +1,8 @@
+#include <iostream>
+
+#include "{{ crate_name }}/greeting.hpp"
+
+int main() {
+    std::cout << {{ crate_name }}::greeting() << '\n';
+    return 0;
+}
@@ -0,0 +1,14 @@
+#include <iostream>
+#include <string>
+
+#include "{{ crate_name }}/greeting.hpp"
+
+int main() {
+    const std::string expected = "Hello from {{ project_name }}!";
+    const std::string actual = {{ crate_name }}::greeting();
+    if (actual != expected) {
+        std::cerr << "expected \"" << expected << "\", got \"" << actual << "\"\n";
+        return 1;
+    }
+    return 0;
+}
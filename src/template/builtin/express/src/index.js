@@ -0,0 +1,12 @@
+import express from "express";
+
+const app = express();
+const port = process.env.PORT ?? 3000;
+
+app.get("/", (req, res) => {
+  res.send("Hello from {{ project_name }}!");
+});
+
+app.listen(port, () => {
+  console.log(`Listening on http://localhost:${port}`);
+});
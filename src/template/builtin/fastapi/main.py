@@ -0,0 +1,8 @@
+from fastapi import FastAPI
+
+app = FastAPI(title="{{ project_name }}")
+
+
+@app.get("/")
+def index() -> dict[str, str]:
+    return {"message": "Hello from {{ project_name }}!"}
@@ -0,0 +1,12 @@
+from flask import Flask
+
+app = Flask(__name__)
+
+
+@app.get("/")
+def index() -> str:
+    return "Hello from {{ project_name }}!"
+
+
+if __name__ == "__main__":
+    app.run(debug=True)
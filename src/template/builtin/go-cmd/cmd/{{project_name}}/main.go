@@ -0,0 +1,11 @@
+package main
+
+import (
+	"fmt"
+
+	"{{ module_path }}/internal/greeting"
+)
+
+func main() {
+	fmt.Println(greeting.For("{{ project_name }}"))
+}
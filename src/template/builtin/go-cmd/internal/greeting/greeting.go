@@ -0,0 +1,7 @@
+// Package greeting builds the message printed by the command.
+package greeting
+
+// For returns the greeting for name.
+func For(name string) string {
+	return "Hello from " + name + "!"
+}
@@ -0,0 +1,10 @@
+package greeting
+
+import "testing"
+
+func TestFor(t *testing.T) {
+	want := "Hello from {{ project_name }}!"
+	if got := For("{{ project_name }}"); got != want {
+		t.Errorf("For() = %q, want %q", got, want)
+	}
+}
@@ -0,0 +1,11 @@
+package main
+
+import "fmt"
+
+func greeting() string {
+	return "Hello from {{ project_name }}!"
+}
+
+func main() {
+	fmt.Println(greeting())
+}
@@ -0,0 +1,10 @@
+package main
+
+import "testing"
+
+func TestGreeting(t *testing.T) {
+	want := "Hello from {{ project_name }}!"
+	if got := greeting(); got != want {
+		t.Errorf("greeting() = %q, want %q", got, want)
+	}
+}
@@ -0,0 +1,30 @@
+plugins {
+    application
+}
+
+group = "{{ group_id }}"
+version = "0.1.0-SNAPSHOT"
+
+repositories {
+    mavenCentral()
+}
+
+dependencies {
+    testImplementation(platform("org.junit:junit-bom:5.11.4"))
+    testImplementation("org.junit.jupiter:junit-jupiter")
+    testRuntimeOnly("org.junit.platform:junit-platform-launcher")
+}
+
+java {
+    toolchain {
+        languageVersion = JavaLanguageVersion.of({{ java_version }})
+    }
+}
+
+application {
+    mainClass = "{{ package }}.Main"
+}
+
+tasks.test {
+    useJUnitPlatform()
+}
@@ -0,0 +1 @@
+rootProject.name = "{{ project_name | kebab_case }}"
@@ -0,0 +1,12 @@
+package {{ package }};
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+
+import org.junit.jupiter.api.Test;
+
+class MainTest {
+    @Test
+    void greetsTheProject() {
+        assertEquals("Hello from {{ project_name }}!", Main.greeting());
+    }
+}
@@ -0,0 +1,11 @@
+package {{ package }};
+
+public class Main {
+    public static String greeting() {
+        return "Hello from {{ project_name }}!";
+    }
+
+    public static void main(String[] args) {
+        System.out.println(greeting());
+    }
+}
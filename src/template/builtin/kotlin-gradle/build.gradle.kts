@@ -0,0 +1,29 @@
+plugins {
+    kotlin("jvm") version "2.1.0"
+    application
+}
+
+group = "{{ group_id }}"
+version = "0.1.0-SNAPSHOT"
+
+repositories {
+    mavenCentral()
+}
+
+dependencies {
+    testImplementation(platform("org.junit:junit-bom:5.11.4"))
+    testImplementation("org.junit.jupiter:junit-jupiter")
+    testRuntimeOnly("org.junit.platform:junit-platform-launcher")
+}
+
+kotlin {
+    jvmToolchain({{ java_version }})
+}
+
+application {
+    mainClass = "{{ package }}.MainKt"
+}
+
+tasks.test {
+    useJUnitPlatform()
+}
@@ -0,0 +1,7 @@
+package {{ package }}
+
+fun greeting(): String = "Hello from {{ project_name }}!"
+
+fun main() {
+    println(greeting())
+}
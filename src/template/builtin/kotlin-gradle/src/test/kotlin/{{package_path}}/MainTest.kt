@@ -0,0 +1,11 @@
+package {{ package }}
+
+import org.junit.jupiter.api.Assertions.assertEquals
+import org.junit.jupiter.api.Test
+
+class MainTest {
+    @Test
+    fun greetsTheProject() {
+        assertEquals("Hello from {{ project_name }}!", greeting())
+    }
+}
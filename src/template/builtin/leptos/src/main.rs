@@ -0,0 +1,17 @@
+use leptos::prelude::*;
+
+#[component]
+fn App() -> impl IntoView {
+    let (count, set_count) = signal(0);
+    view! {
+        <h1>"Hello from {{ project_name }}!"</h1>
+        <button on:click=move |_| *set_count.write() += 1>
+            "Clicked " {count} " times"
+        </button>
+    }
+}
+
+fn main() {
+    console_error_panic_hook::set_once();
+    leptos::mount::mount_to_body(App);
+}
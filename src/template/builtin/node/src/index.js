@@ -0,0 +1,5 @@
+function main() {
+  console.log("Hello from {{ project_name }}!");
+}
+
+main();
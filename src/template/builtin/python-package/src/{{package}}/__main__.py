@@ -0,0 +1,9 @@
+from {{ package }} import greeting
+
+
+def main() -> None:
+    print(greeting())
+
+
+if __name__ == "__main__":
+    main()
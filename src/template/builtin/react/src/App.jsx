@@ -0,0 +1,11 @@
+import { useState } from "react";
+
+export default function App() {
+  const [count, setCount] = useState(0);
+  return (
+    <>
+      <h1>Hello from {{ project_name }}!</h1>
+      <button onClick={() => setCount(count + 1)}>Clicked {count} times</button>
+    </>
+  );
+}
@@ -0,0 +1,11 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use {{ crate_name }}::fibonacci;
+
+fn bench_fibonacci(c: &mut Criterion) {
+    c.bench_function("fibonacci 40", |b| b.iter(|| fibonacci(black_box(40))));
+}
+
+criterion_group!(benches, bench_fibonacci);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+use clap::Parser;
+
+/// {{ project_name }} command line interface
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Who to greet
+    #[arg(default_value = "world")]
+    name: String,
+
+    /// Print the greeting this many times
+    #[arg(short, long, default_value_t = 1)]
+    count: u8,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    for _ in 0..cli.count {
+        println!("Hello, {}!", cli.name);
+    }
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use tokio::time;
+
+#[tokio::main]
+async fn main() {
+    let worker = tokio::spawn(async {
+        let mut interval = time::interval(Duration::from_secs(5));
+        loop {
+            interval.tick().await;
+            println!("{{ project_name }} is running");
+        }
+    });
+
+    tokio::signal::ctrl_c()
+        .await
+        .expect("failed to listen for ctrl-c");
+    println!("Shutting down");
+    worker.abort();
+}
@@ -0,0 +1,12 @@
+package {{ package }};
+
+import org.springframework.web.bind.annotation.GetMapping;
+import org.springframework.web.bind.annotation.RestController;
+
+@RestController
+public class HelloController {
+    @GetMapping("/")
+    public String index() {
+        return "Hello from {{ project_name }}!";
+    }
+}
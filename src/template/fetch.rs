@@ -0,0 +1,158 @@
+use std::path::Path;
+use std::process::Command;
+
+use color_eyre::eyre::{bail, eyre};
+
+use super::{Template, TemplateSource};
+
+/// Loads a template from a `--template` argument.
+///
+/// Accepts a local directory, a git URL (including `file://` URLs, which makes this work
+/// offline), or the name of an installed template. Git URLs may pin a branch or tag with a
+/// `#ref` suffix.
+pub fn load(spec: &str) -> color_eyre::Result<Template> {
+    let path = Path::new(spec);
+    if path.is_dir() {
+        return Template::from_dir(path);
+    }
+    if is_git_url(spec) {
+        return clone(spec);
+    }
+    super::find(spec)?.ok_or_else(|| {
+        eyre!(
+            "'{}' is not a directory, a git URL or the name of an installed template",
+            spec
+        )
+    })
+}
+
+fn is_git_url(spec: &str) -> bool {
+    spec.contains("://") || spec.starts_with("git@") || spec.ends_with(".git")
+}
+
+/// Shallow-clones the repository into a temporary directory and loads it from there. The
+/// clone is removed again once the template has been read into memory.
+fn clone(spec: &str) -> color_eyre::Result<Template> {
+    let (url, reference) = match spec.rsplit_once('#') {
+        Some((url, reference)) => (url, Some(reference)),
+        None => (spec, None),
+    };
+    let checkout = tempfile::tempdir()?;
+
+    let mut command = Command::new("git");
+    command.args(["clone", "--quiet", "--depth", "1"]);
+    if let Some(reference) = reference {
+        command.args(["--branch", reference]);
+    }
+    let output = command.arg(url).arg(checkout.path()).output()?;
+    if !output.status.success() {
+        bail!(
+            "Failed to clone template {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let mut template = Template::from_dir(checkout.path())?;
+    template.source = TemplateSource::Git(spec.to_string());
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "-c",
+                "init.defaultBranch=main",
+                "-c",
+                "commit.gpgsign=false",
+            ])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// A template repository whose README says `v1` at tag `v1` and `v2` on `main`.
+    fn template_repo() -> tempfile::TempDir {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        fs::write(dir.join("template.toml"), "name = \"demo\"\n").unwrap();
+        fs::write(dir.join("README.md"), "v1").unwrap();
+        git(dir, &["init", "--quiet"]);
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "--quiet", "-m", "v1"]);
+        git(dir, &["tag", "v1"]);
+        fs::write(dir.join("README.md"), "v2").unwrap();
+        git(dir, &["commit", "--quiet", "-am", "v2"]);
+        repo
+    }
+
+    fn readme(template: &Template) -> String {
+        let file = template
+            .files
+            .iter()
+            .find(|file| file.path == "README.md")
+            .unwrap();
+        String::from_utf8(file.contents.clone()).unwrap()
+    }
+
+    fn file_url(path: &Path) -> String {
+        format!("file://{}", path.display())
+    }
+
+    #[test]
+    fn loads_a_local_directory_without_git_metadata() {
+        let repo = template_repo();
+        let template = load(repo.path().to_str().unwrap()).unwrap();
+        assert_eq!(template.manifest.name, "demo");
+        assert_eq!(
+            template.source,
+            TemplateSource::Directory(repo.path().to_path_buf())
+        );
+        assert!(template
+            .files
+            .iter()
+            .all(|file| !file.path.starts_with(".git")));
+        assert_eq!(readme(&template), "v2");
+    }
+
+    #[test]
+    fn clones_a_file_url() {
+        let repo = template_repo();
+        let url = file_url(repo.path());
+        let template = load(&url).unwrap();
+        assert_eq!(template.manifest.name, "demo");
+        assert_eq!(template.source, TemplateSource::Git(url));
+        assert_eq!(readme(&template), "v2");
+    }
+
+    #[test]
+    fn clones_a_file_url_at_a_ref() {
+        let repo = template_repo();
+        let url = format!("{}#v1", file_url(repo.path()));
+        let template = load(&url).unwrap();
+        assert_eq!(template.source, TemplateSource::Git(url));
+        assert_eq!(readme(&template), "v1");
+    }
+
+    #[test]
+    fn reports_a_missing_ref() {
+        let repo = template_repo();
+        let url = format!("{}#nope", file_url(repo.path()));
+        let err = load(&url).err().unwrap();
+        assert!(err.to_string().contains("Failed to clone template"));
+    }
+}
@@ -0,0 +1,70 @@
+use serde::Deserialize;
+
+/// Name of the manifest file at the root of every template.
+pub const MANIFEST_FILE: &str = "template.toml";
+
+/// Describes a template: what it is, which variables it asks for and which files are optional.
+///
+/// File names and contents are rendered with `{{ variable }}` and `{{ variable | filter }}`.
+/// Wrap text that has literal `{{` in it, like GitHub Actions' `${{ }}` or JSX's `style={{ }}`,
+/// in `{% raw %}` and `{% endraw %}` to copy it unchanged.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Language name as accepted by `projector new`, e.g. `python`.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Display name of the framework this template sets up, e.g. `FastAPI`. Framework templates
+    /// are offered after their language is picked in `projector new`.
+    #[serde(default)]
+    pub framework: Option<String>,
+    #[serde(default)]
+    pub variables: Vec<Variable>,
+    #[serde(default)]
+    pub conditional: Vec<Conditional>,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+/// A value the user is asked for before rendering. Variables without a prompt are computed from
+/// their default instead, e.g. a package directory derived from the package name.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Variable {
+    pub name: String,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Used with `--yes` or when the answer is left blank. May reference earlier variables,
+    /// e.g. `"{{ project_name | kebab_case }}"`.
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default, rename = "type")]
+    pub kind: VariableKind,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableKind {
+    #[default]
+    String,
+    Bool,
+}
+
+/// Only renders files under `path` when the variable named by `when` is true. `when` may be
+/// negated with a leading `!`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Conditional {
+    pub path: String,
+    pub when: String,
+}
+
+/// A command run inside the project after rendering, e.g. installing dependencies. Arguments may
+/// reference variables. If it fails, the project is rolled back unless the step is `optional`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Step {
+    pub run: Vec<String>,
+    /// Only warn when the command fails, for steps that are easy to rerun by hand.
+    #[serde(default)]
+    pub optional: bool,
+}
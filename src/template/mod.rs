@@ -0,0 +1,423 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Datelike;
+use color_eyre::eyre::{eyre, WrapErr};
+use directories::ProjectDirs;
+use include_dir::{include_dir, Dir};
+
+use crate::scaffold::ScaffoldOptions;
+use crate::state::app::Config;
+use crate::state::languages::ProgrammingLanguage;
+use crate::ui::prompt::Prompter;
+
+pub mod fetch;
+pub mod manifest;
+pub mod render;
+
+use manifest::{Manifest, VariableKind, MANIFEST_FILE};
+use render::{is_truthy, render_str, Variables};
+
+/// Templates shipped inside the binary, one directory per template.
+static BUILTIN: Dir = include_dir!("$CARGO_MANIFEST_DIR/src/template/builtin");
+
+/// A directory tree of files whose names and contents may contain `{{ variables }}`.
+pub struct Template {
+    pub manifest: Manifest,
+    pub source: TemplateSource,
+    files: Vec<TemplateFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSource {
+    Builtin,
+    Directory(PathBuf),
+    Git(String),
+}
+
+struct TemplateFile {
+    /// Path relative to the template root, before rendering.
+    path: String,
+    contents: Vec<u8>,
+}
+
+impl Template {
+    /// Loads a template from a directory containing a `template.toml`.
+    pub fn from_dir(root: &Path) -> color_eyre::Result<Template> {
+        let manifest_path = root.join(MANIFEST_FILE);
+        let manifest = fs::read_to_string(&manifest_path)
+            .wrap_err_with(|| format!("Failed to read {}", manifest_path.display()))?;
+        let manifest: Manifest = toml::from_str(&manifest)
+            .wrap_err_with(|| format!("Invalid {}", manifest_path.display()))?;
+
+        let mut files = Vec::new();
+        collect_files(root, root, &mut files)?;
+        Ok(Template {
+            manifest,
+            source: TemplateSource::Directory(root.to_path_buf()),
+            files,
+        })
+    }
+
+    fn from_embedded(dir: &Dir) -> color_eyre::Result<Template> {
+        let manifest = dir
+            .get_file(dir.path().join(MANIFEST_FILE))
+            .and_then(|file| file.contents_utf8())
+            .ok_or_else(|| eyre!("Built-in template {} has no manifest", dir.path().display()))?;
+        let manifest: Manifest = toml::from_str(manifest)?;
+
+        let mut files = Vec::new();
+        collect_embedded(dir, dir.path(), &mut files);
+        Ok(Template {
+            manifest,
+            source: TemplateSource::Builtin,
+            files,
+        })
+    }
+
+    /// Fills in every variable the manifest declares, keeping any already present in `vars`.
+    ///
+    /// Values come from `--var` flags first, then defaults when running with `--yes`, and
+    /// otherwise from the prompter.
+    pub fn resolve_variables(
+        &self,
+        mut vars: Variables,
+        opts: &ScaffoldOptions,
+        prompter: &mut dyn Prompter,
+    ) -> color_eyre::Result<Variables> {
+        for variable in &self.manifest.variables {
+            if vars.contains_key(&variable.name) {
+                continue;
+            }
+            let default = match &variable.default {
+                Some(default) => render_str(default, &vars)?,
+                None => String::new(),
+            };
+            let value = if let Some(value) = opts.variable(&variable.name) {
+                value.to_string()
+            } else if opts.assume_yes {
+                default
+            } else if let Some(prompt) = &variable.prompt {
+                match variable.kind {
+                    VariableKind::Bool => prompter.confirm(prompt)?.to_string(),
+                    VariableKind::String if default.is_empty() => prompter.input(prompt)?,
+                    VariableKind::String => {
+                        let answer = prompter.input(&format!("{} [{}]", prompt, default))?;
+                        if answer.is_empty() {
+                            default
+                        } else {
+                            answer
+                        }
+                    }
+                }
+            } else {
+                default
+            };
+            vars.insert(variable.name.clone(), value);
+        }
+        Ok(vars)
+    }
+
+    /// Writes the rendered template into `dest`, returning the paths of the files created. With
+    /// `keep_existing`, files already in `dest` are left alone instead of overwritten.
+    pub fn render(
+        &self,
+        vars: &Variables,
+        dest: &Path,
+        keep_existing: bool,
+    ) -> color_eyre::Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for file in &self.files {
+            if !self.is_included(&file.path, vars)? {
+                continue;
+            }
+            let target = dest.join(render_str(&file.path, vars)?);
+            if keep_existing && target.exists() {
+                println!("Keeping existing {}", target.display());
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // Binary files are copied as they are.
+            match std::str::from_utf8(&file.contents) {
+                Ok(text) => fs::write(&target, render_str(text, vars)?),
+                Err(_) => fs::write(&target, &file.contents),
+            }
+            .wrap_err_with(|| format!("Failed to write {}", target.display()))?;
+            written.push(target);
+        }
+        Ok(written)
+    }
+
+    /// The manifest's steps with their arguments rendered, each with whether it's optional.
+    pub fn commands(&self, vars: &Variables) -> color_eyre::Result<Vec<(Vec<String>, bool)>> {
+        let mut commands = Vec::new();
+        for step in &self.manifest.steps {
+            let args = step
+                .run
+                .iter()
+                .map(|arg| render_str(arg, vars))
+                .collect::<color_eyre::Result<Vec<_>>>()?;
+            if !args.is_empty() {
+                commands.push((args, step.optional));
+            }
+        }
+        Ok(commands)
+    }
+
+    fn is_included(&self, path: &str, vars: &Variables) -> color_eyre::Result<bool> {
+        for conditional in &self.manifest.conditional {
+            let prefix = conditional.path.trim_end_matches('/');
+            let matches = path == prefix || path.starts_with(&format!("{}/", prefix));
+            if !matches {
+                continue;
+            }
+            let (negated, name) = match conditional.when.strip_prefix('!') {
+                Some(name) => (true, name.trim()),
+                None => (false, conditional.when.trim()),
+            };
+            let value = vars
+                .get(name)
+                .ok_or_else(|| eyre!("Condition for {} uses unknown variable '{}'", path, name))?;
+            if is_truthy(value) == negated {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Variables every template can use without declaring them. The project is named after the
+/// last component of `project_dir`, which should be an absolute path. JavaScript projects also
+/// get `package_manager`, see [`crate::scaffold::javascript::package_manager`].
+pub fn builtin_variables(project_dir: &Path) -> Variables {
+    let cfg: Config = confy::load("projector", None).unwrap_or_default();
+    variables_from(cfg, project_dir)
+}
+
+/// [`builtin_variables`] from an already loaded config.
+fn variables_from(cfg: Config, project_dir: &Path) -> Variables {
+    let project_name = project_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut vars = render::name_variables(&project_name);
+    // Forward slashes keep the path valid inside JSON and TOML strings on Windows too.
+    vars.insert(
+        "project_dir".to_string(),
+        project_dir.to_string_lossy().replace('\\', "/"),
+    );
+    vars.insert(
+        "author".to_string(),
+        cfg.author.unwrap_or_else(default_author),
+    );
+    vars.insert("license".to_string(), cfg.license);
+    // Includes the trailing slash so templates can write `{{ forge_prefix }}{{ project_name }}`.
+    vars.insert(
+        "forge_prefix".to_string(),
+        cfg.forge_prefix
+            .map(|prefix| format!("{}/", prefix.trim_end_matches('/')))
+            .unwrap_or_default(),
+    );
+    vars.insert("year".to_string(), chrono::Local::now().year().to_string());
+    // For template steps that set up a virtualenv: `{{ python }} -m venv .venv`, then
+    // `{{ venv_python }} -m pip ...`.
+    let windows = cfg!(target_os = "windows");
+    vars.insert(
+        "python".to_string(),
+        if windows { "python" } else { "python3" }.to_string(),
+    );
+    vars.insert(
+        "venv_python".to_string(),
+        if windows {
+            ".venv/Scripts/python.exe"
+        } else {
+            ".venv/bin/python"
+        }
+        .to_string(),
+    );
+    vars
+}
+
+/// Falls back to git's `user.name`, then the login name.
+fn default_author() -> String {
+    std::process::Command::new("git")
+        .args(["config", "user.name"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("USER").ok())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .unwrap_or_default()
+}
+
+/// Directory users can drop their own templates into, one subdirectory per template.
+pub fn user_templates_dir() -> Option<PathBuf> {
+    ProjectDirs::from("rs", "", "projector").map(|dirs| dirs.config_dir().join("templates"))
+}
+
+/// Every available template. User templates come first and shadow built-ins with the same name.
+pub fn all() -> color_eyre::Result<Vec<Template>> {
+    let mut templates = Vec::new();
+    if let Some(dir) = user_templates_dir().filter(|dir| dir.is_dir()) {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.join(MANIFEST_FILE).is_file() {
+                templates.push(Template::from_dir(&path)?);
+            }
+        }
+    }
+    for dir in BUILTIN.dirs() {
+        let template = Template::from_embedded(dir)?;
+        if !templates
+            .iter()
+            .any(|t| t.manifest.name == template.manifest.name)
+        {
+            templates.push(template);
+        }
+    }
+    Ok(templates)
+}
+
+/// Templates declaring a framework for `lang`, e.g. FastAPI for Python.
+pub fn frameworks(lang: ProgrammingLanguage) -> color_eyre::Result<Vec<Template>> {
+    Ok(all()?
+        .into_iter()
+        .filter(|t| t.manifest.framework.is_some())
+        .filter(|t| {
+            t.manifest
+                .language
+                .as_deref()
+                .and_then(ProgrammingLanguage::from_name)
+                == Some(lang)
+        })
+        .collect())
+}
+
+/// Looks up a template by name, preferring user templates over built-ins.
+pub fn find(name: &str) -> color_eyre::Result<Option<Template>> {
+    Ok(all()?.into_iter().find(|t| t.manifest.name == name))
+}
+
+/// Collects the files under `dir`. Symlinks are skipped rather than followed, so a template
+/// can't pull in files from outside its directory or loop back into itself.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<TemplateFile>) -> color_eyre::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = relative_path(root, &path);
+        if relative == MANIFEST_FILE || relative == ".git" {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            eprintln!("Warning: Skipping symlink {} in the template", relative);
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            files.push(TemplateFile {
+                path: relative,
+                contents: fs::read(&path)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn collect_embedded(dir: &Dir, root: &Path, files: &mut Vec<TemplateFile>) {
+    for file in dir.files() {
+        let relative = relative_path(root, file.path());
+        if relative != MANIFEST_FILE {
+            files.push(TemplateFile {
+                path: relative,
+                contents: file.contents().to_vec(),
+            });
+        }
+    }
+    for sub in dir.dirs() {
+        collect_embedded(sub, root, files);
+    }
+}
+
+/// Template paths always use `/` so conditionals work the same on every platform.
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::prompt::ScriptedPrompter;
+
+    #[test]
+    fn builtin_templates_render_with_their_defaults() {
+        let temp = tempfile::tempdir().unwrap();
+        let opts = ScaffoldOptions {
+            assume_yes: true,
+            ..ScaffoldOptions::default()
+        };
+        assert!(BUILTIN.dirs().next().is_some());
+        let mut failures = Vec::new();
+        for dir in BUILTIN.dirs() {
+            let template = Template::from_embedded(dir).unwrap();
+            let name = template.manifest.name.clone();
+            let project_dir = temp.path().join(&name).join("my-app");
+            let mut vars = variables_from(Config::default(), &project_dir);
+            // What `projector new --template` adds for JavaScript.
+            if template.manifest.language.as_deref() == Some("javascript") {
+                vars.insert("package_manager".to_string(), "npm".to_string());
+            }
+            let result = template
+                .resolve_variables(
+                    vars,
+                    &opts,
+                    &mut ScriptedPrompter::new(Vec::<String>::new()),
+                )
+                .and_then(|vars| {
+                    template.commands(&vars)?;
+                    template.render(&vars, &project_dir, false)
+                });
+            match result {
+                Ok(written) if written.is_empty() => {
+                    failures.push(format!("{} wrote nothing", name))
+                }
+                Ok(_) => {}
+                Err(e) => failures.push(format!("{}: {}", name, e)),
+            }
+        }
+        assert!(failures.is_empty(), "{:#?}", failures);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_skipped() {
+        let temp = tempfile::tempdir().unwrap();
+        let outside = temp.path().join("secret.txt");
+        fs::write(&outside, "secret").unwrap();
+        let root = temp.path().join("template");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join(MANIFEST_FILE),
+            "name = \"linked\"\ndescription = \"\"\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("secret.txt")).unwrap();
+        // A link back up would recurse forever if followed.
+        std::os::unix::fs::symlink(&root, root.join("src/loop")).unwrap();
+
+        let template = Template::from_dir(&root).unwrap();
+        let paths: Vec<&str> = template.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/main.rs"]);
+    }
+}
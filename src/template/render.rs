@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use color_eyre::eyre::{bail, eyre};
+
+/// Values substituted into templates, keyed by variable name.
+pub type Variables = BTreeMap<String, String>;
+
+/// Opens a block that's copied as is, for text with literal `{{`, such as GitHub Actions
+/// expressions or JSX style objects. The same syntax as Jinja, so cookiecutter templates keep
+/// working.
+const RAW_START: &str = "{% raw %}";
+const RAW_END: &str = "{% endraw %}";
+
+/// Replaces every `{{ name }}` or `{{ name | filter }}` in `input` with its value. Text between
+/// `{% raw %}` and `{% endraw %}` is copied without substituting anything.
+///
+/// Referencing a variable that isn't defined is an error rather than an empty string, so typos
+/// in templates don't silently produce broken projects.
+pub fn render_str(input: &str, vars: &Variables) -> color_eyre::Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    loop {
+        let variable = rest.find("{{");
+        let raw = rest.find(RAW_START);
+        match (variable, raw) {
+            (_, Some(raw)) if variable.is_none_or(|variable| raw < variable) => {
+                output.push_str(&rest[..raw]);
+                let after = &rest[raw + RAW_START.len()..];
+                let end = after
+                    .find(RAW_END)
+                    .ok_or_else(|| eyre!("Unclosed '{{% raw %}}' in template text"))?;
+                output.push_str(&after[..end]);
+                rest = &after[end + RAW_END.len()..];
+            }
+            (Some(start), _) => {
+                output.push_str(&rest[..start]);
+                let after = &rest[start + 2..];
+                let end = after
+                    .find("}}")
+                    .ok_or_else(|| eyre!("Unclosed '{{{{' in template text"))?;
+                output.push_str(&evaluate(&after[..end], vars)?);
+                rest = &after[end + 2..];
+            }
+            _ => break,
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn evaluate(expression: &str, vars: &Variables) -> color_eyre::Result<String> {
+    let mut parts = expression.split('|').map(str::trim);
+    let name = parts.next().unwrap_or_default();
+    let mut value = vars
+        .get(name)
+        .cloned()
+        .ok_or_else(|| eyre!("Template references unknown variable '{}'", name))?;
+    for filter in parts {
+        value = apply_filter(&value, filter)?;
+    }
+    Ok(value)
+}
+
+fn apply_filter(value: &str, filter: &str) -> color_eyre::Result<String> {
+    Ok(match filter {
+        "snake_case" => snake_case(value),
+        "kebab_case" => kebab_case(value),
+        "pascal_case" => words(value).iter().map(|word| capitalize(word)).collect(),
+        // Turns a dotted package name into a directory path.
+        "path" => value.replace('.', "/"),
+        "upper" => value.to_uppercase(),
+        "lower" => value.to_lowercase(),
+        _ => bail!("Unknown template filter '{}'", filter),
+    })
+}
+
+pub fn snake_case(value: &str) -> String {
+    words(value).join("_")
+}
+
+pub fn kebab_case(value: &str) -> String {
+    words(value).join("-")
+}
+
+/// Splits an identifier-ish string into lowercase words on separators and camelCase humps.
+fn words(value: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in value.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Whether a variable's value counts as "on" for conditional files.
+pub fn is_truthy(value: &str) -> bool {
+    !matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "" | "false" | "no" | "n" | "0"
+    )
+}
+
+/// Derives the conventional forms of a project name, e.g. `crate_name` for Rust and Python.
+pub fn name_variables(project_name: &str) -> Variables {
+    let mut vars = Variables::new();
+    vars.insert("project_name".to_string(), project_name.to_string());
+    vars.insert("crate_name".to_string(), snake_case(project_name));
+    vars.insert(
+        "class_name".to_string(),
+        words(project_name)
+            .iter()
+            .map(|word| capitalize(word))
+            .collect(),
+    );
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> Variables {
+        name_variables("My App")
+    }
+
+    #[test]
+    fn substitutes_variables_and_filters() {
+        let rendered = render_str("{{ project_name }} / {{project_name|kebab_case}}", &vars());
+        assert_eq!(rendered.unwrap(), "My App / my-app");
+    }
+
+    #[test]
+    fn unknown_variables_are_errors() {
+        let err = render_str("{{ missing }}", &vars()).unwrap_err();
+        assert!(err.to_string().contains("unknown variable 'missing'"));
+    }
+
+    #[test]
+    fn raw_blocks_keep_literal_braces() {
+        let input = "name: {{ crate_name }}\n{% raw %}sha: ${{ github.sha }}\n<div style={{ color: 'red' }} />{% endraw %}\n";
+        assert_eq!(
+            render_str(input, &vars()).unwrap(),
+            "name: my_app\nsha: ${{ github.sha }}\n<div style={{ color: 'red' }} />\n"
+        );
+    }
+
+    #[test]
+    fn unclosed_raw_blocks_are_errors() {
+        let err = render_str("{% raw %}${{ x }}", &vars()).unwrap_err();
+        assert!(err.to_string().contains("Unclosed '{% raw %}'"));
+    }
+}
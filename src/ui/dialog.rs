@@ -0,0 +1,245 @@
+use ratatui::style::{Color, Style};
+use ratatui::{
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use super::router::{popup_area, Outcome, Screen, Transition};
+
+/// Yes/no popup. Enter or 'y' answers yes, 'n' or Esc answers no.
+pub struct ConfirmDialog {
+    question: String,
+}
+
+impl ConfirmDialog {
+    pub fn new(question: impl Into<String>) -> Self {
+        ConfirmDialog {
+            question: question.into(),
+        }
+    }
+}
+
+impl<T> Screen<T> for ConfirmDialog {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let area = popup_area(area, 60, 7);
+        frame.render_widget(Clear, area);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Span::styled(
+                "Confirm",
+                Style::default().fg(Color::LightBlue),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [question_area, footer_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+        frame.render_widget(
+            Paragraph::new(self.question.as_str()).wrap(Wrap { trim: true }),
+            question_area,
+        );
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::from(
+                "Press 'y' for Yes, 'n' for No, or Enter to confirm Yes",
+            ))),
+            footer_area,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<T> {
+        match key {
+            KeyCode::Char('y') | KeyCode::Enter => Transition::Return(Outcome::Confirmed(true)),
+            KeyCode::Char('n') | KeyCode::Esc => Transition::Return(Outcome::Confirmed(false)),
+            _ => Transition::Stay,
+        }
+    }
+
+    fn is_modal(&self) -> bool {
+        true
+    }
+}
+
+/// Single-line text input popup. Enter submits, Esc cancels.
+pub struct InputDialog {
+    question: String,
+    input: String,
+}
+
+impl InputDialog {
+    pub fn new(question: impl Into<String>) -> Self {
+        InputDialog {
+            question: question.into(),
+            input: String::new(),
+        }
+    }
+
+    /// Pre-fills the input, e.g. with the current value when renaming.
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.input = value.into();
+        self
+    }
+}
+
+impl<T> Screen<T> for InputDialog {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let area = popup_area(area, 60, 8);
+        frame.render_widget(Clear, area);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(Span::styled("Input", Style::default().fg(Color::LightBlue)));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [question_area, input_area, footer_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+        frame.render_widget(
+            Paragraph::new(self.question.as_str()).wrap(Wrap { trim: true }),
+            question_area,
+        );
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::from(self.input.as_str())))
+                .block(Block::bordered().border_type(BorderType::Rounded)),
+            input_area,
+        );
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::from("Enter to submit, Esc to cancel"))),
+            footer_area,
+        );
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<T> {
+        match key {
+            KeyCode::Enter => {
+                Transition::Return(Outcome::Submitted(Some(self.input.trim().to_string())))
+            }
+            KeyCode::Esc => Transition::Return(Outcome::Submitted(None)),
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                Transition::Stay
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                Transition::Stay
+            }
+            _ => Transition::Stay,
+        }
+    }
+
+    fn is_modal(&self) -> bool {
+        true
+    }
+}
+
+/// Popup list of actions. Enter picks the highlighted entry, Esc or 'q' closes the menu.
+pub struct MenuDialog {
+    title: String,
+    options: Vec<String>,
+    state: ListState,
+}
+
+impl MenuDialog {
+    pub fn new(title: impl Into<String>, options: &[&str]) -> Self {
+        MenuDialog {
+            title: title.into(),
+            options: options.iter().map(|option| option.to_string()).collect(),
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+}
+
+impl<T> Screen<T> for MenuDialog {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let area = popup_area(area, 40, self.options.len() as u16 + 2);
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = self
+            .options
+            .iter()
+            .map(|option| ListItem::new(Line::from(option.as_str())))
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title(Span::styled(
+                        self.title.as_str(),
+                        Style::default().fg(Color::Magenta),
+                    )),
+            )
+            .highlight_style(Style::default().fg(Color::LightMagenta))
+            .highlight_symbol(">> ");
+        frame.render_stateful_widget(list, area, &mut self.state);
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<T> {
+        match key {
+            KeyCode::Up => self.state.select_previous(),
+            KeyCode::Down if self.state.selected() < Some(self.options.len() - 1) => {
+                self.state.select_next()
+            }
+            KeyCode::Enter => return Transition::Return(Outcome::Selected(self.state.selected())),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                return Transition::Return(Outcome::Selected(None))
+            }
+            _ => {}
+        }
+        Transition::Stay
+    }
+
+    fn is_modal(&self) -> bool {
+        true
+    }
+}
+
+/// Full-screen message with a title, dismissed with Esc. Exits the session when it is the
+/// only screen.
+pub struct MessageScreen {
+    title: String,
+    message: String,
+}
+
+impl MessageScreen {
+    pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+        MessageScreen {
+            title: title.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl<T> Screen<T> for MessageScreen {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let vertical = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+        let message_paragraph = Paragraph::new(Line::from(self.message.as_str())).block(
+            Block::bordered()
+                .title(Span::styled(
+                    self.title.as_str(),
+                    Style::default().fg(Color::Red),
+                ))
+                .border_type(BorderType::Rounded),
+        );
+        frame.render_widget(message_paragraph, vertical[1]);
+
+        let footer = Paragraph::new(Line::from(Span::from("Press escape to exit")));
+        frame.render_widget(footer, vertical[2]);
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<T> {
+        match key {
+            KeyCode::Esc => Transition::Pop,
+            _ => Transition::Stay,
+        }
+    }
+}
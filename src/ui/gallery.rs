@@ -1,92 +1,163 @@
-use crate::database::manage::{get_all_projects, setup_database};
+use crate::database::manage::{delete_project, get_all_projects, rename_project, setup_database};
 use crate::database::model::Project;
+use crate::pins;
 use crate::post_setup;
+use crate::scaffold::ScaffoldOptions;
 use ratatui::style::{Color, Style};
 use ratatui::{
-    crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEventKind},
-    layout::{Constraint, Layout},
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Rect},
     text::{Line, Span},
     widgets::{Block, List, ListItem, ListState, Paragraph},
-    DefaultTerminal, Frame,
+    Frame,
 };
-use std::io::{self};
+use rusqlite::Connection;
 
-use super::yn;
+use super::dialog::{ConfirmDialog, InputDialog, MenuDialog, MessageScreen};
+use super::prompt::Prompter;
+use super::router::{Outcome, Router, Screen, Transition};
 
-#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
-struct Config {
-    editor: String,
-}
-
-/// Show the interactive setup screen
-pub fn show() -> color_eyre::Result<()> {
-    blank()?;
+const ACTIONS: [&str; 4] = ["Open", "Rename", "Delete", "Back"];
 
-    let terminal = ratatui::init();
+/// Show the project gallery. `opts` apply to opening the chosen project, e.g. `--yes`
+/// declining to install pinned toolchains.
+pub fn show(opts: &ScaffoldOptions, prompter: &mut dyn Prompter) -> color_eyre::Result<()> {
     let conn = setup_database()?;
     let projects = get_all_projects(&conn)?;
-    let result = run(terminal, &conn, &projects);
-    ratatui::restore();
-    result
-}
-
-fn run(
-    mut terminal: DefaultTerminal,
-    conn: &rusqlite::Connection,
-    projects: &[Project],
-) -> color_eyre::Result<()> {
     if projects.is_empty() {
         return Err(color_eyre::eyre::eyre!(
             "No projects found in the database."
         ));
     }
 
-    let mut index = 0;
-    loop {
-        terminal.draw(|frame| render(frame, projects, index))?;
-        if let Ok(key) = read_key() {
-            match key {
-                Key::Up => {
-                    if index > 0 {
-                        index -= 1;
-                    }
-                }
-                Key::Down => {
-                    if index < projects.len() - 1 {
-                        index += 1;
-                    }
-                }
-                Key::Enter => {
-                    // Reset the terminal
-                    ratatui::restore();
-
-                    // Open the selected project
-                    open_project(&projects[index])?;
-                    break Ok(());
-                }
-                Key::Delete => {
-                    ratatui::restore();
-                    if yn::ask("Are you sure you want to delete this project? (y/n)")? {
-                        std::fs::remove_dir_all(&projects[index].path)?;
-                        crate::database::manage::delete_project(conn, projects[index].id)?;
-                    }
-                    show()?;
-                    break Ok(()); // TODO: ensure this doesn't create deep recursion
-                }
-                Key::Quit => break Ok(()),
-                Key::Other => {}
+    let screen = GalleryScreen {
+        conn,
+        projects,
+        index: 0,
+        pending: None,
+    };
+    // Opening hands the terminal to the editor, so it happens after the TUI has been torn down.
+    if let Some(project) = Router::new(Box::new(screen)).run()? {
+        open_project(&project, opts, prompter)?;
+    }
+    Ok(())
+}
+
+/// The dialog the gallery is waiting on, so it knows what an outcome refers to.
+enum Pending {
+    Actions,
+    Rename,
+    Delete,
+}
+
+struct GalleryScreen {
+    conn: Connection,
+    projects: Vec<Project>,
+    index: usize,
+    pending: Option<Pending>,
+}
+
+impl GalleryScreen {
+    fn selected(&self) -> Option<&Project> {
+        self.projects.get(self.index)
+    }
+
+    fn confirm_delete(&mut self) -> Transition<Project> {
+        let Some(project) = self.selected() else {
+            return Transition::Stay;
+        };
+        let question = format!(
+            "Are you sure you want to delete {}? This removes {} from disk.",
+            project.name, project.path
+        );
+        self.pending = Some(Pending::Delete);
+        Transition::Push(Box::new(ConfirmDialog::new(question)))
+    }
+
+    fn delete_selected(&mut self) -> color_eyre::Result<()> {
+        if let Some(project) = self.selected() {
+            std::fs::remove_dir_all(&project.path)?;
+            delete_project(&self.conn, project.id)?;
+        }
+        self.reload()
+    }
+
+    fn rename_selected(&mut self, name: &str) -> color_eyre::Result<()> {
+        if let Some(project) = self.selected() {
+            rename_project(&self.conn, project.id, name)?;
+        }
+        self.reload()
+    }
+
+    fn reload(&mut self) -> color_eyre::Result<()> {
+        self.projects = get_all_projects(&self.conn)?;
+        self.index = self.index.min(self.projects.len().saturating_sub(1));
+        Ok(())
+    }
+
+    fn report(result: color_eyre::Result<()>) -> Transition<Project> {
+        match result {
+            Ok(()) => Transition::Stay,
+            Err(e) => Transition::Push(Box::new(MessageScreen::new("Error", e.to_string()))),
+        }
+    }
+}
+
+impl Screen<Project> for GalleryScreen {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        render(frame, area, &self.projects, self.index);
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<Project> {
+        match key {
+            KeyCode::Up => self.index = self.index.saturating_sub(1),
+            KeyCode::Down if self.index + 1 < self.projects.len() => self.index += 1,
+            KeyCode::Enter if self.selected().is_some() => {
+                self.pending = Some(Pending::Actions);
+                return Transition::Push(Box::new(MenuDialog::new("Actions", &ACTIONS)));
+            }
+            KeyCode::Char('d') => return self.confirm_delete(),
+            KeyCode::Esc | KeyCode::Char('q') => return Transition::Exit(None),
+            _ => {}
+        }
+        Transition::Stay
+    }
+
+    fn resume(&mut self, outcome: Outcome) -> Transition<Project> {
+        match (self.pending.take(), outcome) {
+            (Some(Pending::Actions), Outcome::Selected(Some(0))) => {
+                Transition::Exit(self.projects.get(self.index).cloned())
+            }
+            (Some(Pending::Actions), Outcome::Selected(Some(1))) => {
+                let Some(project) = self.selected() else {
+                    return Transition::Stay;
+                };
+                let dialog =
+                    InputDialog::new("New name for the project?").with_value(&project.name);
+                self.pending = Some(Pending::Rename);
+                Transition::Push(Box::new(dialog))
+            }
+            (Some(Pending::Actions), Outcome::Selected(Some(2))) => self.confirm_delete(),
+            (Some(Pending::Rename), Outcome::Submitted(Some(name))) if !name.is_empty() => {
+                let result = self.rename_selected(&name);
+                Self::report(result)
+            }
+            (Some(Pending::Delete), Outcome::Confirmed(true)) => {
+                let result = self.delete_selected();
+                Self::report(result)
             }
+            _ => Transition::Stay,
         }
     }
 }
 
-fn render(frame: &mut Frame, projects: &[Project], selected_index: usize) {
+fn render(frame: &mut Frame, area: Rect, projects: &[Project], selected_index: usize) {
     let vertical = Layout::vertical([
         Constraint::Length(1),
         Constraint::Fill(1),
         Constraint::Length(1),
     ])
-    .split(frame.area());
+    .split(area);
 
     let header = Paragraph::new(Line::from("Projector - Project Gallery"))
         .block(Block::default())
@@ -125,44 +196,17 @@ fn render(frame: &mut Frame, projects: &[Project], selected_index: usize) {
     frame.render_stateful_widget(project_list, vertical[1], &mut state);
 
     let footer = Paragraph::new(Line::from(Span::from(
-        "Use Up/Down to navigate, Enter to open, d to delete, Esc or Q to exit",
+        "Use Up/Down to navigate, Enter for actions, d to delete, Esc or Q to exit",
     )));
     frame.render_widget(footer, vertical[2]);
 }
 
-fn read_key() -> Result<Key, io::Error> {
-    if let CrosstermEvent::Key(key_event) = event::read()? {
-        if key_event.kind == KeyEventKind::Press {
-            match key_event.code {
-                KeyCode::Up => return Ok(Key::Up),
-                KeyCode::Down => return Ok(Key::Down),
-                KeyCode::Enter => return Ok(Key::Enter),
-                KeyCode::Char('q') => return Ok(Key::Quit),
-                KeyCode::Char('d') => return Ok(Key::Delete),
-                _ => return Ok(Key::Other),
-            }
-        }
-    }
-    Ok(Key::Other)
-}
-
-fn open_project(project: &Project) -> Result<(), io::Error> {
-    post_setup::editor::run_editor_setup(std::path::Path::new(&project.path))
-        .expect("Failed to open editor");
-    Ok(())
-}
-
-enum Key {
-    Up,
-    Down,
-    Enter,
-    Quit,
-    Delete,
-    Other,
-}
-
-fn blank() -> color_eyre::Result<()> {
-    ratatui::init();
-    ratatui::restore();
-    Ok(())
+fn open_project(
+    project: &Project,
+    opts: &ScaffoldOptions,
+    prompter: &mut dyn Prompter,
+) -> color_eyre::Result<()> {
+    let path = std::path::Path::new(&project.path);
+    pins::check(path, Some(project.type_lang), opts, prompter)?;
+    post_setup::open(path, project.type_lang, opts, prompter)
 }
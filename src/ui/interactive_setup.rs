@@ -1,76 +1,60 @@
-use crate::scaffold;
+use crate::scaffold::{self, ScaffoldOptions};
 use crate::state::app::App;
 use crate::state::languages::ProgrammingLanguage;
-use crate::ui::not_implemented_warning;
+use crate::ui::prompt::Prompter;
+use crate::ui::router::{Router, Screen, Transition};
 use ratatui::style::{Color, Style};
 use ratatui::{
-    crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEventKind},
-    layout::{Constraint, Layout},
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Rect},
     text::{Line, Span},
     widgets::{Block, BorderType, List, ListItem, ListState, Paragraph},
-    DefaultTerminal, Frame,
+    Frame,
 };
 
 /// Show the interactive setup screen
-pub fn show() -> color_eyre::Result<()> {
-    blank()?;
-
-    let terminal = ratatui::init();
-    let mut app = App::new();
-    let result = run(terminal, &mut app);
-    ratatui::restore();
-    if let Some(lang) = app.selected_lang {
-        match lang {
-            ProgrammingLanguage::Rust => {
-                println!("Rust selected. Setting up Rust environment...");
-                scaffold::rust::setup(None);
-            }
-            ProgrammingLanguage::C => {
-                println!("C selected. Setting up C environment...");
-                scaffold::c::setup(None);
-            }
-            ProgrammingLanguage::Python => {
-                println!("Python selected. Setting up Python environment...");
-                scaffold::python::setup(None);
-            }
-            _ => {
-                not_implemented_warning::show(format!(
-                    "Scaffolding for language '{:?}' is not implemented yet.",
-                    lang
-                ))?;
-            }
-        }
+pub fn show(opts: &ScaffoldOptions, prompter: &mut dyn Prompter) -> color_eyre::Result<()> {
+    let mut router = Router::new(Box::new(SetupScreen { app: App::new() }));
+    // With the TUI prompter, the questions after this one stay in the same session.
+    let lang = match prompter.session() {
+        Some(session) => router.run_in(session)?,
+        None => router.run()?,
+    };
+    if let Some(lang) = lang {
+        scaffold::setup(lang, None, opts, prompter)?;
     }
-    result
+    Ok(())
+}
+
+/// Language chooser. Exits with the highlighted language on Enter.
+struct SetupScreen {
+    app: App,
 }
 
-fn run(mut terminal: DefaultTerminal, app: &mut App) -> color_eyre::Result<()> {
-    loop {
-        terminal.draw(|frame| render(frame, app))?;
-        if let CrosstermEvent::Key(key_event) = event::read()? {
-            if key_event.kind == KeyEventKind::Press {
-                match key_event.code {
-                    KeyCode::Esc => break Ok(()),
-                    KeyCode::Char('q') => break Ok(()),
-                    KeyCode::Up => app.prev_item(),
-                    KeyCode::Down => app.next_item(),
-                    KeyCode::Enter => {
-                        break Ok(());
-                    }
-                    _ => {}
-                }
-            }
+impl Screen<ProgrammingLanguage> for SetupScreen {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        render(frame, area, &self.app);
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<ProgrammingLanguage> {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => return Transition::Exit(None),
+            KeyCode::Up => self.app.prev_item(),
+            KeyCode::Down => self.app.next_item(),
+            KeyCode::Enter => return Transition::Exit(self.app.selected_lang),
+            _ => {}
         }
+        Transition::Stay
     }
 }
 
-fn render(frame: &mut Frame, app: &App) {
+fn render(frame: &mut Frame, area: Rect, app: &App) {
     let vertical = Layout::vertical([
         Constraint::Length(1),
         Constraint::Fill(1),
         Constraint::Length(1),
     ])
-    .split(frame.area());
+    .split(area);
 
     let header = Paragraph::new(Line::from("Projector - Interactive Setup"))
         .block(Block::default())
@@ -108,14 +92,7 @@ fn render(frame: &mut Frame, app: &App) {
     frame.render_stateful_widget(languages_list, vertical[1], &mut state);
 
     let footer = Paragraph::new(Line::from(Span::from(
-        "Use Up/Down to navigate, Esc or Q to exit",
+        "Use Up/Down to navigate, Enter to select, Esc or Q to exit",
     )));
     frame.render_widget(footer, vertical[2]);
 }
-
-/// Blank the screen
-fn blank() -> color_eyre::Result<()> {
-    ratatui::init();
-    ratatui::restore();
-    Ok(())
-}
@@ -1,6 +1,9 @@
+pub mod dialog;
 pub mod gallery;
 pub mod grab_string;
 pub mod interactive_setup;
 pub mod not_implemented_warning;
+pub mod prompt;
+pub mod router;
 pub mod settings;
 pub mod yn;
@@ -0,0 +1,237 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+
+use color_eyre::eyre::eyre;
+
+use super::dialog::MenuDialog;
+use super::router::{Dialogs, Outcome, Session};
+use super::{grab_string, yn};
+
+/// Asks the user questions. Scaffolders and database helpers take one of these instead of
+/// talking to the terminal directly, so the same flow works in the TUI, over a pipe, or in tests.
+pub trait Prompter {
+    /// Asks a yes/no question.
+    fn confirm(&mut self, question: &str) -> color_eyre::Result<bool>;
+
+    /// Asks for a line of free-form text. The answer is returned trimmed.
+    fn input(&mut self, question: &str) -> color_eyre::Result<String>;
+
+    /// Asks the user to pick one of `options`, returning its index.
+    fn select(&mut self, question: &str, options: &[&str]) -> color_eyre::Result<usize>;
+
+    /// Gives the terminal back for ordinary output, e.g. before a plan prints and runs its
+    /// steps. The next question takes it again.
+    fn suspend(&mut self) {}
+
+    /// The full-screen session questions are asked in, for screens that should share it, like
+    /// the language chooser. `None` for prompters that don't use the TUI.
+    fn session(&mut self) -> Option<&mut Session> {
+        None
+    }
+}
+
+/// Picks the prompter to use for this run.
+///
+/// An answers file always wins. Otherwise the full-screen TUI is used, unless `plain` was
+/// requested or stdin is not a terminal, in which case questions are asked line by line.
+pub fn select(plain: bool, answers: Option<&Path>) -> color_eyre::Result<Box<dyn Prompter>> {
+    if let Some(path) = answers {
+        return Ok(Box::new(ScriptedPrompter::from_file(path)?));
+    }
+    if plain || !io::stdin().is_terminal() {
+        return Ok(Box::new(PlainPrompter::stdio()));
+    }
+    Ok(Box::new(TuiPrompter::new()))
+}
+
+/// Asks questions as ratatui dialogs, keeping one full-screen session up from the first
+/// question until [`Prompter::suspend`], so a run of questions doesn't flicker between them.
+pub struct TuiPrompter {
+    dialogs: Dialogs,
+}
+
+impl TuiPrompter {
+    pub fn new() -> Self {
+        TuiPrompter {
+            dialogs: Dialogs::new(),
+        }
+    }
+}
+
+impl Prompter for TuiPrompter {
+    fn confirm(&mut self, question: &str) -> color_eyre::Result<bool> {
+        yn::ask(&mut self.dialogs, question)
+    }
+
+    fn input(&mut self, question: &str) -> color_eyre::Result<String> {
+        grab_string::ask(&mut self.dialogs, question)
+    }
+
+    fn select(&mut self, question: &str, options: &[&str]) -> color_eyre::Result<usize> {
+        match self
+            .dialogs
+            .ask(Box::new(MenuDialog::new(question, options)))?
+        {
+            Some(Outcome::Selected(Some(index))) => Ok(index),
+            _ => Err(eyre!("No option chosen for: {}", question)),
+        }
+    }
+
+    fn suspend(&mut self) {
+        self.dialogs.suspend();
+    }
+
+    fn session(&mut self) -> Option<&mut Session> {
+        Some(self.dialogs.session())
+    }
+}
+
+/// Asks questions as plain lines of text, for pipes, dumb terminals and `--plain`.
+pub struct PlainPrompter<R, W> {
+    reader: R,
+    writer: W,
+}
+
+impl PlainPrompter<io::StdinLock<'static>, io::Stderr> {
+    /// Reads answers from stdin and writes questions to stderr, keeping stdout clean for output.
+    pub fn stdio() -> Self {
+        PlainPrompter::new(io::stdin().lock(), io::stderr())
+    }
+}
+
+impl<R: BufRead, W: Write> PlainPrompter<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        PlainPrompter { reader, writer }
+    }
+
+    fn read_answer(&mut self, question: &str) -> color_eyre::Result<String> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(eyre!(
+                "Reached end of input while waiting for: {}",
+                question
+            ));
+        }
+        Ok(line.trim().to_string())
+    }
+}
+
+impl<R: BufRead, W: Write> Prompter for PlainPrompter<R, W> {
+    fn confirm(&mut self, question: &str) -> color_eyre::Result<bool> {
+        // Questions are usually written for the TUI with a "(y/n)" hint of their own.
+        let shown = question.trim_end_matches("(y/n)").trim_end();
+        loop {
+            write!(self.writer, "{} [Y/n] ", shown)?;
+            self.writer.flush()?;
+            // Matches the TUI prompt, where Enter confirms.
+            match parse_yes_no(&self.read_answer(question)?) {
+                Some(answer) => return Ok(answer),
+                None => writeln!(self.writer, "Please answer 'y' or 'n'.")?,
+            }
+        }
+    }
+
+    fn input(&mut self, question: &str) -> color_eyre::Result<String> {
+        write!(self.writer, "{} ", question)?;
+        self.writer.flush()?;
+        self.read_answer(question)
+    }
+
+    fn select(&mut self, question: &str, options: &[&str]) -> color_eyre::Result<usize> {
+        writeln!(self.writer, "{}", question)?;
+        for (i, option) in options.iter().enumerate() {
+            writeln!(self.writer, "  {}) {}", i + 1, option)?;
+        }
+        loop {
+            write!(self.writer, "Choice [1]: ")?;
+            self.writer.flush()?;
+            let answer = self.read_answer(question)?;
+            if answer.is_empty() {
+                return Ok(0);
+            }
+            match parse_choice(&answer, options) {
+                Some(index) => return Ok(index),
+                None => writeln!(self.writer, "Please pick a number from the list.")?,
+            }
+        }
+    }
+}
+
+/// Answers questions from a fixed queue, in order. Used by `--answers` and in tests.
+pub struct ScriptedPrompter {
+    answers: VecDeque<String>,
+}
+
+impl ScriptedPrompter {
+    pub fn new<I, S>(answers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ScriptedPrompter {
+            answers: answers.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Loads answers from a file with one answer per line.
+    pub fn from_file(path: &Path) -> color_eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(ScriptedPrompter::new(contents.lines()))
+    }
+
+    fn next_answer(&mut self, question: &str) -> color_eyre::Result<String> {
+        self.answers
+            .pop_front()
+            .map(|answer| answer.trim().to_string())
+            .ok_or_else(|| eyre!("No scripted answer left for: {}", question))
+    }
+}
+
+impl Prompter for ScriptedPrompter {
+    fn confirm(&mut self, question: &str) -> color_eyre::Result<bool> {
+        let answer = self.next_answer(question)?;
+        parse_yes_no(&answer).ok_or_else(|| {
+            eyre!(
+                "Scripted answer '{}' is not yes or no for: {}",
+                answer,
+                question
+            )
+        })
+    }
+
+    fn input(&mut self, question: &str) -> color_eyre::Result<String> {
+        self.next_answer(question)
+    }
+
+    fn select(&mut self, question: &str, options: &[&str]) -> color_eyre::Result<usize> {
+        let answer = self.next_answer(question)?;
+        parse_choice(&answer, options).ok_or_else(|| {
+            eyre!(
+                "Scripted answer '{}' is not one of {:?} for: {}",
+                answer,
+                options,
+                question
+            )
+        })
+    }
+}
+
+/// An empty answer counts as yes, like pressing Enter on the TUI prompt.
+fn parse_yes_no(answer: &str) -> Option<bool> {
+    match answer.to_ascii_lowercase().as_str() {
+        "" | "y" | "yes" | "true" => Some(true),
+        "n" | "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Accepts either a 1-based position in the list or the option's text.
+fn parse_choice(answer: &str, options: &[&str]) -> Option<usize> {
+    if let Ok(number) = answer.parse::<usize>() {
+        return (1..=options.len()).contains(&number).then(|| number - 1);
+    }
+    options
+        .iter()
+        .position(|option| option.eq_ignore_ascii_case(answer))
+}
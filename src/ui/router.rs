@@ -0,0 +1,212 @@
+use ratatui::style::{Color, Style};
+use ratatui::{
+    crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEventKind},
+    layout::{Constraint, Flex, Layout, Rect},
+    text::Line,
+    widgets::{Block, Paragraph},
+    DefaultTerminal, Frame,
+};
+
+/// What a dialog hands back to the screen that opened it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Confirmed(bool),
+    /// Text from an input dialog, or `None` if it was cancelled.
+    Submitted(Option<String>),
+    /// Index of the chosen menu entry, or `None` if the menu was closed.
+    Selected(Option<usize>),
+}
+
+/// What the router should do after a screen handled a key or an outcome.
+pub enum Transition<T> {
+    Stay,
+    Push(Box<dyn Screen<T>>),
+    Pop,
+    /// Pops the current screen and passes the outcome to the one below it.
+    Return(Outcome),
+    /// Leaves the TUI, handing `T` back to whoever started it.
+    Exit(Option<T>),
+}
+
+/// A screen or modal popup living on the router's stack. `T` is what the whole session
+/// produces when it exits, e.g. the language picked in the setup screen.
+pub trait Screen<T> {
+    fn render(&mut self, frame: &mut Frame, area: Rect);
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<T>;
+
+    /// Called when a dialog pushed by this screen returns.
+    fn resume(&mut self, _outcome: Outcome) -> Transition<T> {
+        Transition::Stay
+    }
+
+    /// Modal screens are drawn as popups on top of the screen below instead of replacing it.
+    fn is_modal(&self) -> bool {
+        false
+    }
+}
+
+/// The terminal while the TUI is up: raw mode on the alternate screen. Dropping it gives the
+/// terminal back.
+pub struct Session {
+    terminal: DefaultTerminal,
+}
+
+impl Session {
+    pub fn enter() -> Self {
+        Session {
+            terminal: ratatui::init(),
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+/// Routes input to the topmost screen of a stack, in a session of its own or one it shares.
+pub struct Router<T> {
+    stack: Vec<Box<dyn Screen<T>>>,
+}
+
+impl<T> Router<T> {
+    pub fn new(root: Box<dyn Screen<T>>) -> Self {
+        Router { stack: vec![root] }
+    }
+
+    /// Enters the alternate screen and runs until a screen exits or the stack is empty.
+    pub fn run(mut self) -> color_eyre::Result<Option<T>> {
+        self.run_in(&mut Session::enter())
+    }
+
+    /// Like [`Router::run`], but in a session that stays up afterwards, so the next screen
+    /// doesn't flicker out of and back into the alternate screen.
+    pub fn run_in(&mut self, session: &mut Session) -> color_eyre::Result<Option<T>> {
+        self.event_loop(&mut session.terminal)
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> color_eyre::Result<Option<T>> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            let CrosstermEvent::Key(key_event) = event::read()? else {
+                continue;
+            };
+            if key_event.kind != KeyEventKind::Press {
+                continue;
+            }
+            let Some(top) = self.stack.last_mut() else {
+                return Ok(None);
+            };
+            let mut transition = top.handle_key(key_event.code);
+            loop {
+                match transition {
+                    Transition::Stay => break,
+                    Transition::Push(screen) => {
+                        self.stack.push(screen);
+                        break;
+                    }
+                    Transition::Pop => {
+                        self.stack.pop();
+                        if self.stack.is_empty() {
+                            return Ok(None);
+                        }
+                        break;
+                    }
+                    Transition::Return(outcome) => {
+                        self.stack.pop();
+                        match self.stack.last_mut() {
+                            Some(below) => transition = below.resume(outcome),
+                            None => return Ok(None),
+                        }
+                    }
+                    Transition::Exit(value) => return Ok(value),
+                }
+            }
+        }
+    }
+
+    /// Draws the topmost full screen and every modal stacked above it.
+    fn render(&mut self, frame: &mut Frame) {
+        let base = self
+            .stack
+            .iter()
+            .rposition(|screen| !screen.is_modal())
+            .unwrap_or(0);
+        let area = frame.area();
+        for screen in &mut self.stack[base..] {
+            screen.render(frame, area);
+        }
+    }
+}
+
+/// Asks one dialog after another over a plain backdrop, all in one session that's entered on
+/// the first question and kept until [`Dialogs::suspend`] or drop.
+pub struct Dialogs {
+    session: Option<Session>,
+    router: Router<Outcome>,
+}
+
+impl Dialogs {
+    pub fn new() -> Self {
+        Dialogs {
+            session: None,
+            router: Router::new(Box::new(DialogHost)),
+        }
+    }
+
+    /// Shows `dialog` and returns its outcome, or `None` if it was dismissed with Esc.
+    pub fn ask(&mut self, dialog: Box<dyn Screen<Outcome>>) -> color_eyre::Result<Option<Outcome>> {
+        // Only the backdrop is left between questions.
+        self.router.stack.truncate(1);
+        self.router.stack.push(dialog);
+        let session = self.session.get_or_insert_with(Session::enter);
+        self.router.run_in(session)
+    }
+
+    /// The session the dialogs are shown in, entering it if it isn't up.
+    pub fn session(&mut self) -> &mut Session {
+        self.session.get_or_insert_with(Session::enter)
+    }
+
+    /// Leaves the alternate screen until the next question.
+    pub fn suspend(&mut self) {
+        self.session = None;
+    }
+}
+
+/// Empty backdrop for [`Dialogs`] that exits with whatever the dialog returns.
+struct DialogHost;
+
+impl Screen<Outcome> for DialogHost {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Paragraph::new(Line::from("Projector"))
+            .block(Block::default())
+            .style(Style::default().fg(Color::LightBlue))
+            .centered();
+        frame.render_widget(header, Rect { height: 1, ..area });
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<Outcome> {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => Transition::Exit(None),
+            _ => Transition::Stay,
+        }
+    }
+
+    fn resume(&mut self, outcome: Outcome) -> Transition<Outcome> {
+        Transition::Exit(Some(outcome))
+    }
+}
+
+/// Returns a rectangle of `width` percent and `height` rows centered in `area`.
+pub fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
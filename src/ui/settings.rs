@@ -1,52 +1,61 @@
 use crate::state::app::App;
 use ratatui::style::{Color, Style};
 use ratatui::{
-    crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEventKind},
-    layout::{Constraint, Layout},
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Rect},
     text::{Line, Span},
     widgets::{Block, List, ListItem, ListState, Paragraph},
-    DefaultTerminal, Frame,
+    Frame,
 };
 
+use super::dialog::MessageScreen;
+use super::router::{Router, Screen, Transition};
+
 pub fn show() -> color_eyre::Result<()> {
-    let terminal = ratatui::init();
     let mut app = App::new();
     app.load_settings()?;
-    let result = run(terminal, &mut app);
-    ratatui::restore();
-    result
+    Router::<()>::new(Box::new(SettingsScreen { app })).run()?;
+    Ok(())
+}
+
+struct SettingsScreen {
+    app: App,
 }
 
-fn run(mut terminal: DefaultTerminal, app: &mut App) -> color_eyre::Result<()> {
-    loop {
-        terminal.draw(|frame| render(frame, app))?;
-        if let CrosstermEvent::Key(key_event) = event::read()? {
-            if key_event.kind == KeyEventKind::Press {
-                match key_event.code {
-                    KeyCode::Esc => break Ok(()),
-                    KeyCode::Char('q') => break Ok(()),
-                    KeyCode::Up => app.prev_setting(),
-                    KeyCode::Down => app.next_setting(),
-                    KeyCode::Left => app.prev_option(),
-                    KeyCode::Right => app.next_option(),
-                    KeyCode::Enter => {
-                        app.save_settings()?;
-                        break Ok(());
-                    }
-                    _ => {}
-                }
+impl Screen<()> for SettingsScreen {
+    fn render(&mut self, frame: &mut Frame, area: Rect) {
+        render(frame, area, &self.app);
+    }
+
+    fn handle_key(&mut self, key: KeyCode) -> Transition<()> {
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => return Transition::Exit(None),
+            KeyCode::Up => self.app.prev_setting(),
+            KeyCode::Down => self.app.next_setting(),
+            KeyCode::Left => self.app.prev_option(),
+            KeyCode::Right => self.app.next_option(),
+            KeyCode::Enter => {
+                return match self.app.save_settings() {
+                    Ok(()) => Transition::Exit(None),
+                    Err(e) => Transition::Push(Box::new(MessageScreen::new(
+                        "Error",
+                        format!("Failed to save settings: {}", e),
+                    ))),
+                };
             }
+            _ => {}
         }
+        Transition::Stay
     }
 }
 
-fn render(frame: &mut Frame, app: &App) {
+fn render(frame: &mut Frame, area: Rect, app: &App) {
     let vertical = Layout::vertical([
         Constraint::Length(1),
         Constraint::Fill(1),
         Constraint::Length(1),
     ])
-    .split(frame.area());
+    .split(area);
 
     let header = Paragraph::new(Line::from("Projector - Settings"))
         .block(Block::default())
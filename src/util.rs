@@ -1,12 +1,45 @@
-use std::{env, process::Command};
+use std::{
+    env,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
 
-pub fn is_tool_installed(tool: &str) -> bool {
-    if env::var("FAKE_UNINSTALLED").is_ok() {
-        return false;
+/// Whether an executable called `program` is in one of the PATH directories, without running
+/// it. For tools like package managers that don't all understand `--version`.
+pub fn is_on_path(program: &str) -> bool {
+    which(program).is_some()
+}
+
+/// Where `program` would be run from: the first match in the PATH directories, or `program`
+/// itself if it's a path. On Windows, `.exe`, `.cmd` and `.bat` are tried as well.
+pub fn which(program: &str) -> Option<PathBuf> {
+    which_in(program, &env::var_os("PATH")?)
+}
+
+/// Like [`which`], searching `path` instead of the PATH variable.
+pub fn which_in(program: &str, path: &OsStr) -> Option<PathBuf> {
+    let program_path = Path::new(program);
+    if program_path.components().count() > 1 {
+        return executable(program_path);
+    }
+    env::split_paths(path).find_map(|dir| executable(&dir.join(program)))
+}
+
+#[cfg(unix)]
+fn executable(path: &Path) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .then(|| path.to_path_buf())
+}
+
+#[cfg(not(unix))]
+fn executable(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
     }
-    Command::new(tool)
-        .arg("--version")
-        .output()
-        .map(|output| output.status.success())
-        .unwrap_or(false)
+    ["exe", "cmd", "bat"]
+        .iter()
+        .map(|extension| path.with_extension(extension))
+        .find(|path| path.is_file())
 }